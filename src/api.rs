@@ -0,0 +1,177 @@
+use crate::Task;
+use eframe::egui;
+use serde::Deserialize;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+// A request from the HTTP thread, answered on the UI thread which owns the tasks
+pub enum ApiCall {
+    List,
+    // No priority means the default from the settings
+    Add { text: String, priority: Option<u8> },
+    Done(Uuid),
+}
+
+pub struct ApiReply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiReply {
+    pub fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "error": message }).to_string();
+        Self { status, body }
+    }
+}
+
+pub fn task_list(tasks: &[Task]) -> String {
+    serde_json::to_string(tasks).unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+struct NewTask {
+    text: String,
+    #[serde(default)]
+    priority: Option<u8>,
+}
+
+type Call = (ApiCall, mpsc::Sender<ApiReply>);
+
+// The local HTTP server, stopped when dropped
+pub struct ApiServer {
+    server: Arc<Server>,
+    calls: mpsc::Receiver<Call>,
+    port: u16,
+}
+
+impl ApiServer {
+    // Listen on localhost only. `ctx` is woken for each call so it gets answered
+    // even while the window is idle.
+    pub fn start(port: u16, ctx: egui::Context) -> Result<Self, String> {
+        let server = Server::http(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+        let server = Arc::new(server);
+        let (sender, calls) = mpsc::channel::<Call>();
+
+        let listener = server.clone();
+        thread::spawn(move || {
+            for mut request in listener.incoming_requests() {
+                let reply = match parse(&mut request) {
+                    Ok(call) => {
+                        let (reply_to, reply) = mpsc::channel();
+                        if sender.send((call, reply_to)).is_err() {
+                            break;
+                        }
+                        ctx.request_repaint();
+                        reply
+                            .recv_timeout(Duration::from_secs(5))
+                            .unwrap_or_else(|_| ApiReply::error(503, "the app did not answer"))
+                    }
+                    Err(reply) => reply,
+                };
+                let content_type = Header::from_bytes("Content-Type", "application/json")
+                    .expect("static header is valid");
+                let response = Response::from_string(reply.body)
+                    .with_status_code(reply.status)
+                    .with_header(content_type);
+                request.respond(response).ok();
+            }
+        });
+
+        Ok(Self {
+            server,
+            calls,
+            port,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    // Calls waiting to be answered
+    pub fn pending(&self) -> Vec<Call> {
+        self.calls.try_iter().collect()
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn parse(request: &mut Request) -> Result<ApiCall, ApiReply> {
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // A web page can only send a JSON content type cross-origin after a preflight,
+    // which is never answered, so requiring it keeps pages from posting to the API
+    if request.method() == &Method::Post {
+        let content_type = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Content-Type"))
+            .map(|h| h.value.as_str());
+        if !content_type.is_some_and(is_json) {
+            return Err(ApiReply::error(
+                415,
+                "Content-Type must be application/json",
+            ));
+        }
+    }
+
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["tasks"]) => Ok(ApiCall::List),
+        (Method::Post, ["tasks"]) => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .read_to_string(&mut body)
+                .map_err(|_| ApiReply::error(400, "could not read the request body"))?;
+            let new: NewTask = serde_json::from_str(&body)
+                .map_err(|e| ApiReply::error(400, &format!("invalid task: {}", e)))?;
+            if new.text.trim().is_empty() {
+                return Err(ApiReply::error(400, "text is empty"));
+            }
+            if new.priority.is_some_and(|p| !(1..=10).contains(&p)) {
+                return Err(ApiReply::error(400, "priority must be 1-10"));
+            }
+            Ok(ApiCall::Add {
+                text: new.text,
+                priority: new.priority,
+            })
+        }
+        (Method::Post, ["tasks", id, "done"]) => id
+            .parse()
+            .map(ApiCall::Done)
+            .map_err(|_| ApiReply::error(404, "no such task")),
+        _ => Err(ApiReply::error(404, "not found")),
+    }
+}
+
+fn is_json(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or_default();
+    media_type.trim().eq_ignore_ascii_case("application/json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_json_content_types_are_accepted() {
+        assert!(is_json("application/json"));
+        assert!(is_json("Application/JSON; charset=utf-8"));
+        assert!(!is_json("text/plain"));
+        assert!(!is_json("application/x-www-form-urlencoded"));
+        assert!(!is_json(""));
+    }
+}
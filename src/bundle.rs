@@ -0,0 +1,66 @@
+use crate::history;
+use crate::settings::Settings;
+use crate::Task;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const VERSION: u32 = 1;
+
+// Everything the widget keeps, in one JSON file for moving to another machine
+#[derive(Serialize, Deserialize)]
+pub struct Bundle {
+    pub version: u32,
+    pub tasks: Vec<Task>,
+    pub settings: Settings,
+    // The completion log, if it was included
+    #[serde(default)]
+    pub history: Option<String>,
+}
+
+impl Bundle {
+    pub fn new(tasks: &[Task], settings: &Settings, include_history: bool) -> Self {
+        Self {
+            version: VERSION,
+            tasks: tasks.to_vec(),
+            settings: settings.clone(),
+            history: include_history.then(history::read_all).flatten(),
+        }
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        let serialized = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, serialized).map_err(|e| e.to_string())
+    }
+
+    pub fn read(path: &str) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let bundle: Bundle =
+            serde_json::from_str(&data).map_err(|e| format!("not a task bundle: {}", e))?;
+        if bundle.version > VERSION {
+            return Err(format!(
+                "made by a newer version (bundle version {})",
+                bundle.version
+            ));
+        }
+        Ok(bundle)
+    }
+
+    // What importing would replace, for confirming first
+    pub fn summary(&self, current_tasks: usize) -> String {
+        let mut lines = vec![
+            format!(
+                "Replaces the {} current tasks with {} from the bundle",
+                current_tasks,
+                self.tasks.len()
+            ),
+            "Replaces all settings".to_string(),
+        ];
+        if let Some(history) = &self.history {
+            lines.push(format!(
+                "Replaces the completion history with {} entries",
+                history.lines().count()
+            ));
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{inbox, MyApp, Settings};
+use eframe::egui::{self, Key, Vec2};
+
+// A window with just an input: Enter saves the task to the list and closes it,
+// Escape closes without saving
+#[derive(Default)]
+struct CaptureApp {
+    text: String,
+    error: Option<String>,
+}
+
+impl eframe::App for CaptureApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.text)
+                    .hint_text("New task")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+
+            if ui.input(|i| i.key_pressed(Key::Escape)) {
+                frame.close();
+            } else if ui.input(|i| i.key_pressed(Key::Enter)) {
+                match save(&self.text) {
+                    Ok(()) => frame.close(),
+                    Err(err) => self.error = Some(format!("Could not save: {}", err)),
+                }
+            }
+        });
+    }
+}
+
+// Straight into the data file, or through the inbox while the widget is open
+// since it would save its own copy over the file
+fn save(text: &str) -> std::io::Result<()> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    if inbox::is_locked() {
+        return inbox::append(&[text.to_string()]);
+    }
+    let settings = Settings::load();
+    let mut app = MyApp {
+        tasks: MyApp::load_tasks(&settings),
+        new_task_priority: settings.default_priority,
+        settings,
+        ..Default::default()
+    };
+    app.new_task_text = text.to_string();
+    app.add_task();
+    app.persist_tasks()
+}
+
+pub fn run() -> eframe::Result<()> {
+    let options = eframe::NativeOptions {
+        initial_window_size: Some(Vec2::new(360.0, 60.0)),
+        always_on_top: true,
+        resizable: false,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "New task",
+        options,
+        Box::new(|_cc| Box::<CaptureApp>::default()),
+    )
+}
@@ -0,0 +1,95 @@
+use crate::Task;
+
+// How the current list differs from another copy of it, by task text
+#[derive(Default)]
+pub struct Diff {
+    // Only in the current list
+    pub added: Vec<String>,
+    // Only in the other copy
+    pub removed: Vec<String>,
+    // In both, with the fields that differ
+    pub modified: Vec<(String, Vec<&'static str>)>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} updated, {} removed",
+            self.added.len(),
+            self.modified.len(),
+            self.removed.len()
+        )
+    }
+}
+
+// Tasks are paired by id, or by text for copies whose ids were made separately
+pub fn diff(current: &[Task], other: &[Task]) -> Diff {
+    let mut result = Diff::default();
+    let mut paired = vec![false; current.len()];
+    for theirs in other {
+        let unpaired = |i: &usize| !paired[*i];
+        let ours = (0..current.len())
+            .filter(unpaired)
+            .find(|&i| current[i].id == theirs.id)
+            .or_else(|| {
+                (0..current.len())
+                    .filter(unpaired)
+                    .find(|&i| current[i].text.trim() == theirs.text.trim())
+            });
+        let Some(i) = ours else {
+            result.removed.push(theirs.text.clone());
+            continue;
+        };
+        paired[i] = true;
+        let changes = changed_fields(&current[i], theirs);
+        if !changes.is_empty() {
+            result.modified.push((current[i].text.clone(), changes));
+        }
+    }
+    for (task, _) in current.iter().zip(&paired).filter(|(_, &p)| !p) {
+        result.added.push(task.text.clone());
+    }
+    result
+}
+
+fn changed_fields(ours: &Task, theirs: &Task) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if ours.text != theirs.text {
+        changes.push("text");
+    }
+    if ours.shown_priority() != theirs.shown_priority() {
+        changes.push("priority");
+    }
+    if ours.status != theirs.status {
+        changes.push("status");
+    }
+    if ours.color != theirs.color {
+        changes.push("color");
+    }
+    if ours.flagged != theirs.flagged {
+        changes.push("flag");
+    }
+    if ours.icon != theirs.icon {
+        changes.push("icon");
+    }
+    if ours.link != theirs.link {
+        changes.push("link");
+    }
+    if ours.context != theirs.context {
+        changes.push("context");
+    }
+    if ours.progress != theirs.progress {
+        changes.push("progress");
+    }
+    if ours.remind_at != theirs.remind_at {
+        changes.push("reminder");
+    }
+    if ours.due != theirs.due || ours.due_time != theirs.due_time {
+        changes.push("due");
+    }
+    changes
+}
@@ -0,0 +1,205 @@
+use crate::{Status, Task};
+use chrono::Utc;
+
+// Which tasks an export covers
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportScope {
+    All,
+    Visible,
+    Selected,
+}
+
+impl ExportScope {
+    pub const ALL: [ExportScope; 3] = [
+        ExportScope::All,
+        ExportScope::Visible,
+        ExportScope::Selected,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportScope::All => "All tasks",
+            ExportScope::Visible => "Visible tasks",
+            ExportScope::Selected => "Selected tasks",
+        }
+    }
+}
+
+// A Markdown checklist, one item per task
+pub fn markdown(tasks: &[&Task]) -> String {
+    let mut out = String::new();
+    for task in tasks {
+        let check = if task.is_done() { "x" } else { " " };
+        out.push_str(&format!(
+            "- [{}] {} (priority {})\n",
+            check,
+            task.text.replace('\n', " "),
+            task.shown_priority()
+        ));
+    }
+    out
+}
+
+pub fn csv(tasks: &[&Task]) -> String {
+    let mut out = String::from("text,priority,status,flagged,color,link,context\n");
+    for task in tasks {
+        let [r, g, b, a] = task.color;
+        out.push_str(&format!(
+            "{},{},{},{},#{:02x}{:02x}{:02x}{:02x},{},{}\n",
+            csv_field(&task.text),
+            task.shown_priority(),
+            task.status.label(),
+            task.flagged,
+            r,
+            g,
+            b,
+            a,
+            csv_field(task.link.as_deref().unwrap_or_default()),
+            csv_field(task.context.as_deref().unwrap_or_default())
+        ));
+    }
+    out
+}
+
+// Quote a field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// A calendar of to-dos, one VTODO per task with a due date
+pub fn icalendar(tasks: &[&Task]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//taskWidget//EN".to_string(),
+    ];
+    lines.extend(tasks.iter().filter_map(|task| vtodo(task)).flatten());
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+// The unfolded lines of one task's VTODO, or None for a task without a due date
+fn vtodo(task: &Task) -> Option<Vec<String>> {
+    let due = task.due?;
+    let stamp = task
+        .modified
+        .map_or_else(Utc::now, |m| m.with_timezone(&Utc));
+    let mut lines = vec!["BEGIN:VTODO".to_string()];
+    lines.push(format!("UID:{}", task.id));
+    lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+    lines.push(format!("SUMMARY:{}", ics_text(&task.text)));
+    match task.due_at().filter(|_| task.due_time.is_some()) {
+        Some(at) => lines.push(format!(
+            "DUE:{}",
+            at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ")
+        )),
+        None => lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d"))),
+    }
+    // iCalendar counts 1 as the highest priority and 9 as the lowest; hand-edited
+    // files can hold priorities past 10, so don't let the subtraction underflow
+    lines.push(format!(
+        "PRIORITY:{}",
+        11u8.saturating_sub(task.shown_priority()).clamp(1, 9)
+    ));
+    if task.progress > 0 {
+        lines.push(format!("PERCENT-COMPLETE:{}", task.progress.min(100)));
+    }
+    let status = match task.status {
+        Status::Done => "COMPLETED",
+        Status::Active | Status::Waiting => "NEEDS-ACTION",
+    };
+    lines.push(format!("STATUS:{}", status));
+    lines.push("END:VTODO".to_string());
+    Some(lines)
+}
+
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 bytes continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(fields: serde_json::Value) -> Task {
+        let mut value = json!({"text": "Pay rent", "priority": 5, "selected": false});
+        value
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn line<'a>(lines: &'a [String], prefix: &str) -> &'a str {
+        lines.iter().find(|l| l.starts_with(prefix)).unwrap()
+    }
+
+    #[test]
+    fn vtodo_needs_a_due_date() {
+        assert!(vtodo(&task(json!({}))).is_none());
+    }
+
+    #[test]
+    fn vtodo_maps_priority_onto_the_icalendar_scale() {
+        for (priority, expected) in [(10, "PRIORITY:1"), (5, "PRIORITY:6"), (1, "PRIORITY:9")] {
+            let lines = vtodo(&task(json!({"priority": priority, "due": "2024-05-01"}))).unwrap();
+            assert_eq!(line(&lines, "PRIORITY"), expected);
+        }
+        // Out of range in a hand-edited file
+        let lines = vtodo(&task(json!({"priority": 200, "due": "2024-05-01"}))).unwrap();
+        assert_eq!(line(&lines, "PRIORITY"), "PRIORITY:1");
+    }
+
+    #[test]
+    fn vtodo_writes_a_date_only_due_and_escapes_text() {
+        let lines = vtodo(&task(json!({
+            "text": "Milk, eggs; bread\nand jam",
+            "due": "2024-05-01",
+            "status": "Done",
+        })))
+        .unwrap();
+        assert_eq!(lines.first().unwrap(), "BEGIN:VTODO");
+        assert_eq!(lines.last().unwrap(), "END:VTODO");
+        assert_eq!(line(&lines, "DUE"), "DUE;VALUE=DATE:20240501");
+        assert_eq!(
+            line(&lines, "SUMMARY"),
+            r"SUMMARY:Milk\, eggs\; bread\nand jam"
+        );
+        assert_eq!(line(&lines, "STATUS"), "STATUS:COMPLETED");
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_bytes() {
+        let folded = fold(&"x".repeat(160));
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(parts[1].len(), 75);
+        assert!(parts[1].starts_with(' '));
+    }
+}
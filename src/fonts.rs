@@ -0,0 +1,29 @@
+use eframe::egui;
+use egui::{FontData, FontDefinitions, FontFamily};
+use std::fs;
+
+const CUSTOM_FONT: &str = "custom";
+
+// Put the font at `path` ahead of egui's bundled fonts, which stay behind it as
+// fallbacks so emoji and symbols it lacks still render. An empty path restores the defaults.
+pub fn apply(ctx: &egui::Context, path: &str) -> Result<(), String> {
+    let mut fonts = FontDefinitions::default();
+    let path = path.trim();
+    if !path.is_empty() {
+        let bytes = fs::read(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+        // egui panics on font data it can't parse, so check it first
+        if ab_glyph::FontRef::try_from_slice(&bytes).is_err() {
+            return Err(format!("{} is not a TTF/OTF font", path));
+        }
+        fonts
+            .font_data
+            .insert(CUSTOM_FONT.to_owned(), FontData::from_owned(bytes));
+        fonts
+            .families
+            .entry(FontFamily::Proportional)
+            .or_default()
+            .insert(0, CUSTOM_FONT.to_owned());
+    }
+    ctx.set_fonts(fonts);
+    Ok(())
+}
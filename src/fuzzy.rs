@@ -0,0 +1,30 @@
+// Score how well `query` matches `text` as a case-insensitive subsequence,
+// or None if some query character can't be found in order. Higher is better:
+// consecutive characters and matches at word starts count extra, gaps cost a little.
+pub fn score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - pos) as i32;
+
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,51 @@
+use chrono::Local;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const FILE_NAME: &str = "rust_tasks.json";
+
+// Write the tasks into the repository and commit them if they changed,
+// then optionally pull and push. Errors carry git's own message.
+pub fn sync(repo: &Path, serialized: &str, push: bool) -> Result<(), String> {
+    if repo.as_os_str().is_empty() {
+        return Err("No repository set".to_string());
+    }
+    fs::write(repo.join(FILE_NAME), serialized)
+        .map_err(|e| format!("Could not write to {}: {}", repo.display(), e))?;
+    git(repo, &["add", FILE_NAME])?;
+
+    // Nothing staged means the tasks are unchanged since the last commit
+    let unchanged = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["diff", "--cached", "--quiet", "--", FILE_NAME])
+        .status()
+        .map_err(|e| format!("Could not run git: {}", e))?
+        .success();
+    if !unchanged {
+        let message = format!("Update tasks {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+        git(repo, &["commit", "-m", &message, "--", FILE_NAME])?;
+    }
+
+    if push {
+        git(repo, &["pull", "--rebase"])?;
+        git(repo, &["push"])?;
+    }
+    Ok(())
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run git: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("git {} failed: {}", args[0], stderr.trim()))
+    }
+}
@@ -0,0 +1,88 @@
+use crate::Task;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+thread_local! {
+    // Completions logged since the last take_logged
+    static LOGGED: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Serialize)]
+struct Completion<'a> {
+    text: &'a str,
+    priority: u8,
+    completed_at: DateTime<Local>,
+}
+
+#[derive(Deserialize)]
+struct LoggedAt {
+    completed_at: DateTime<Local>,
+}
+
+// Append a line for a completed task. Earlier lines are never rewritten, and the
+// write is synced so a crash can at worst lose the line being written.
+pub fn log_completion(task: &Task) -> io::Result<()> {
+    let path = get_history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    let line = serde_json::to_string(&Completion {
+        text: &task.text,
+        priority: task.shown_priority(),
+        completed_at: Local::now(),
+    })?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    file.sync_data()?;
+    LOGGED.with(|count| count.set(count.get() + 1));
+    Ok(())
+}
+
+pub fn take_logged() -> usize {
+    LOGGED.with(|count| count.replace(0))
+}
+
+// Consecutive days with at least one completion, up to today. A streak that
+// reached yesterday still counts until today is over.
+pub fn streak(today: NaiveDate) -> u32 {
+    let days: BTreeSet<NaiveDate> = read_all()
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<LoggedAt>(line).ok())
+        .map(|entry| entry.completed_at.date_naive())
+        .collect();
+
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+// The whole log, for carrying it over to another machine
+pub fn read_all() -> Option<String> {
+    std::fs::read_to_string(get_history_path()?).ok()
+}
+
+pub fn replace_all(contents: &str) -> io::Result<()> {
+    let path = get_history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    std::fs::write(path, contents)
+}
+
+fn get_history_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks_history.log");
+        path
+    })
+}
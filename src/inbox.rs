@@ -0,0 +1,77 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+// While the widget is open it holds a lock file and saves its own copy of the list
+// over the data file. Other processes (capture, stdin import) append to the inbox
+// instead, and the widget adds whatever it finds there on its next frame.
+
+pub fn lock() -> io::Result<()> {
+    let path = lock_path().ok_or_else(no_data_dir)?;
+    fs::write(path, std::process::id().to_string())
+}
+
+pub fn unlock() {
+    if let Some(path) = lock_path() {
+        fs::remove_file(path).ok();
+    }
+}
+
+// A lock left behind by a crash only sends tasks through the inbox, which the
+// next start empties, so nothing is lost either way
+pub fn is_locked() -> bool {
+    lock_path().is_some_and(|path| path.exists())
+}
+
+// One JSON string per line, so any text survives the round trip
+pub fn append(texts: &[String]) -> io::Result<()> {
+    let path = inbox_path().ok_or_else(no_data_dir)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for text in texts {
+        writeln!(file, "{}", serde_json::to_string(text)?)?;
+    }
+    file.sync_data()
+}
+
+// Everything waiting in the inbox, emptying it. The file is moved aside first so a
+// line appended meanwhile starts a new inbox instead of being deleted unread.
+pub fn take() -> Vec<String> {
+    let (Some(inbox), Some(taken)) = (inbox_path(), taken_path()) else {
+        return Vec::new();
+    };
+    if !inbox.exists() || fs::rename(&inbox, &taken).is_err() {
+        return Vec::new();
+    }
+    let texts = fs::read_to_string(&taken)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    fs::remove_file(taken).ok();
+    texts
+}
+
+fn no_data_dir() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no data directory")
+}
+
+fn lock_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks.lock");
+        path
+    })
+}
+
+fn inbox_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks.inbox");
+        path
+    })
+}
+
+fn taken_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks.inbox.taken");
+        path
+    })
+}
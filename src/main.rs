@@ -1,21 +1,274 @@
-use eframe::egui::{self, Color32, Context, Key, Vec2};
+mod api;
+mod bundle;
+mod capture;
+mod diff;
+mod export;
+mod fonts;
+mod fuzzy;
+mod git_sync;
+mod history;
+mod inbox;
+mod merge;
+mod palette;
+mod review;
+mod settings;
+mod sort;
+mod validation;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use eframe::egui::{self, Color32, Context, Key, Modifiers, Vec2};
+use export::ExportScope;
+use palette::PaletteKind;
+use review::ReviewFrequency;
 use serde::{Deserialize, Serialize};
+use settings::{Dock, NewTaskPosition, PriorityDisplay, Settings, Side, TaskFont, TextCleanup};
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use validation::Diagnostic;
+
+// Color given to tasks that haven't been recolored
+const DEFAULT_TASK_COLOR: Color32 = Color32::WHITE;
+
+// How far (in points) the pointer must move after pressing a task before it
+// counts as a drag. Matches egui's click distance so a press is either one or the other.
+const DRAG_THRESHOLD: f32 = 6.0;
+
+// Icons offered in a task's context menu; any short text can also be typed
+const TASK_ICONS: [&str; 10] = ["⭐", "🔥", "📌", "💡", "📞", "📧", "🛒", "🏠", "💼", "🐛"];
+
+// Scroll distance (in points) per priority step; one mouse wheel notch in egui-winit
+const PRIORITY_SCROLL_STEP: f32 = 50.0;
+
+// How long a newly added task's row flashes
+const ADDED_FLASH: Duration = Duration::from_secs(1);
+
+// How long a toast stays up
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+// Task text longer than this gets a hint while editing
+const LONG_TASK_CHARS: usize = 120;
+
+// Pasting this many lines into the add row asks before creating the tasks
+const CONFIRM_PASTE_AT: usize = 10;
+
+// Completing this many shown tasks at once asks first
+const CONFIRM_COMPLETE_AT: usize = 10;
+
+// Streak lengths, in days, that get a celebration
+const STREAK_MILESTONES: [u32; 7] = [3, 7, 14, 30, 50, 100, 365];
+
+// How often the window wakes to look for tasks captured by other processes
+const INBOX_CHECK: Duration = Duration::from_secs(2);
+
+// How long closing waits for a git sync already running before skipping its own
+const GIT_SYNC_EXIT_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+enum Status {
+    #[default]
+    Active,
+    // Blocked on someone or something else
+    Waiting,
+    Done,
+}
+
+impl Status {
+    const ALL: [Status; 3] = [Status::Active, Status::Waiting, Status::Done];
+
+    fn title(self) -> &'static str {
+        match self {
+            Status::Active => "Active",
+            Status::Waiting => "Waiting",
+            Status::Done => "Done",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Status::Active => "active",
+            Status::Waiting => "waiting",
+            Status::Done => "done",
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 struct Task {
+    // Stable identity across reorders, edits and merges; files from before ids get
+    // fresh ones when loaded
+    #[serde(default = "Uuid::new_v4")]
+    id: Uuid,
     text: String,
-    priority: u8,
+    // Fractional so a task dropped between two others can sit between their
+    // priorities; shown rounded
+    priority: f32,
+    #[serde(
+        deserialize_with = "validation::deserialize_color",
+        default = "default_color"
+    )]
     color: [u8; 4], // RGBA color array
     selected: bool,
+    #[serde(default)]
+    status: Status,
+    // Older files keep completion in a bool; read it so it can be moved into `status`
+    #[serde(default, rename = "done", skip_serializing)]
+    legacy_done: bool,
+    // Optional emoji or short text drawn before the task text
+    #[serde(default)]
+    icon: Option<String>,
+    // Highlight that leaves priority and order alone
+    #[serde(default)]
+    flagged: bool,
+    // Web page or file tied to the task, opened from the row
+    #[serde(default)]
+    link: Option<String>,
+    // Where the task can be done, like @home or @errands; one per task
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default)]
+    due: Option<NaiveDate>,
+    // A nudge at a set time, separate from the due date; fires once
+    #[serde(default)]
+    remind_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    reminded: bool,
+    // How far along the task is, in percent
+    #[serde(default)]
+    progress: u8,
+    // Time of day on the due date; without one the task is due at the end of the day
+    #[serde(default)]
+    due_time: Option<NaiveTime>,
+    #[serde(default)]
+    created: Option<DateTime<Local>>,
+    // Last change to the task, used to pick a side when merging
+    #[serde(default)]
+    modified: Option<DateTime<Local>>,
 
     #[serde(skip)]
     editing: bool,
 
     #[serde(skip)]
     editing_priority: bool,
+
+    // Text from before the current edit, restored if the edit is left empty
+    #[serde(skip)]
+    text_before_edit: String,
+
+    // Show all lines even when the line cap setting would clip them
+    #[serde(skip)]
+    expanded: bool,
+
+    // When the task was added this session, for the highlight flash
+    #[serde(skip)]
+    added_at: Option<Instant>,
+}
+
+impl Task {
+    fn is_done(&self) -> bool {
+        self.status == Status::Done
+    }
+
+    fn shown_priority(&self) -> u8 {
+        self.priority.round() as u8
+    }
+
+    fn start_editing(&mut self) {
+        self.text_before_edit = self.text.clone();
+        self.editing = true;
+    }
+
+    // Commit the edit, reverting to the previous text if it was cleared
+    fn finish_editing(&mut self, cleanup: TextCleanup) {
+        if self.text.trim().is_empty() {
+            self.text = std::mem::take(&mut self.text_before_edit);
+        } else {
+            self.text = cleanup.apply(&self.text);
+            if self.text != self.text_before_edit {
+                self.touch();
+            }
+        }
+        self.editing = false;
+    }
+
+    fn touch(&mut self) {
+        self.modified = Some(Local::now());
+    }
+
+    fn due_at(&self) -> Option<DateTime<Local>> {
+        let time = self
+            .due_time
+            .or_else(|| NaiveTime::from_hms_opt(23, 59, 59))?;
+        Local
+            .from_local_datetime(&self.due?.and_time(time))
+            .earliest()
+    }
+
+    // Unfinished and created at least `days` days ago; 0 turns this off
+    fn is_stale(&self, days: u32, now: DateTime<Local>) -> bool {
+        days > 0 && !self.is_done() && self.age_days(now).is_some_and(|age| age >= days as i64)
+    }
+
+    fn age_days(&self, now: DateTime<Local>) -> Option<i64> {
+        Some((now - self.created?).num_days())
+    }
+
+    // Only active tasks; waiting ones are on someone else and stay out of overdue views
+    fn is_overdue(&self, now: DateTime<Local>) -> bool {
+        self.status == Status::Active && self.due_at().is_some_and(|due| due < now)
+    }
+
+    // Hours and minutes for a timed due within a day of now, otherwise the date
+    fn due_label(&self, now: DateTime<Local>) -> Option<String> {
+        let due = self.due_at()?;
+        if self.due_time.is_none() {
+            return Some(due.format("%b %-d").to_string());
+        }
+        let left = due - now;
+        let minutes = left.num_minutes().abs();
+        if minutes >= 24 * 60 {
+            return Some(due.format("%b %-d %H:%M").to_string());
+        }
+        let span = if minutes >= 60 {
+            format!("{}h {}m", minutes / 60, minutes % 60)
+        } else {
+            format!("{}m", minutes)
+        };
+        Some(if left.num_seconds() >= 0 {
+            format!("in {}", span)
+        } else {
+            format!("{} ago", span)
+        })
+    }
+}
+
+// Display-only filters on the list; a task has to pass all of them to be shown
+#[derive(Clone, Default)]
+struct Filter {
+    flagged_only: bool,
+    waiting_only: bool,
+    color: Option<[u8; 4]>,
+    // Only tasks this many days old or more, if set
+    stale_only: Option<u32>,
+    context: Option<String>,
+}
+
+impl Filter {
+    // `now` is read once per frame or action, so every task is judged against the same day
+    fn shows(&self, task: &Task, now: DateTime<Local>) -> bool {
+        (!self.flagged_only || task.flagged)
+            && (!self.waiting_only || task.status == Status::Waiting)
+            && self.color.is_none_or(|c| c == task.color)
+            && self.stale_only.is_none_or(|days| task.is_stale(days, now))
+            && self
+                .context
+                .as_ref()
+                .is_none_or(|context| task.context.as_ref() == Some(context))
+    }
 }
 
 struct MyApp {
@@ -23,10 +276,100 @@ struct MyApp {
     new_task_text: String,
     new_task_priority: u8,
     new_task_color: Color32,
+    // Set once a color is picked for the add row; until then the priority's color is used
+    new_task_color_chosen: bool,
+    focus_new_task: bool,
+    settings: Settings,
+    show_settings: bool,
+    filter: Filter,
+    // Query typed into the Ctrl+K jump box while it's open, and the highlighted match
+    quick_open: Option<String>,
+    quick_open_choice: usize,
+    // Task to bring into view on the next frame, and where to align it
+    scroll_to_task: Option<(usize, Option<egui::Align>)>,
+    // Keyboard position in the list, which can differ from the selection
+    cursor: Option<Uuid>,
+    // Rows that fit in the list last frame, the distance PageUp/PageDown jump
+    page_rows: usize,
+    // Path typed into the merge window while it's open, and how the last merge went
+    merge_path: Option<String>,
+    merge_result: Option<String>,
+    // What merging the file would change, and its tasks to merge once confirmed
+    merge_preview: Option<(diff::Diff, Vec<Task>)>,
+    // Path typed into the bundle window, and a read bundle awaiting confirmation
+    bundle_path: Option<String>,
+    bundle_include_history: bool,
+    bundle_status: Option<String>,
+    bundle_pending: Option<(bundle::Bundle, diff::Diff)>,
+    // Path typed into the compare window, and the file's tasks with their diff
+    compare_path: Option<String>,
+    compare_result: Option<Result<(diff::Diff, Vec<Task>), String>>,
+    // Asking before deleting a large selection
+    confirm_delete: bool,
+    // Asking before completing many shown tasks
+    confirm_complete_shown: bool,
+    // P was pressed and the next digit sets the selection's priority
+    priority_shortcut: bool,
+    // Adding from the add row with deferred sorting on, and whether a sort is owed
+    adding_in_bulk: bool,
+    sort_pending: bool,
+    // The task deleted most recently and the task it was below (None at the top), for Shift+U
+    last_deleted: Option<(Option<Uuid>, Task)>,
+    // Texts of tasks whose reminders have fired and not been dismissed
+    reminders_shown: Vec<String>,
+    // Lines pasted into the add row, waiting for confirmation before becoming tasks
+    pending_paste: Option<Vec<String>>,
+    // Short message shown at the bottom of the window, and when it appeared
+    toast: Option<(String, Instant)>,
+    // Git sync running in the background, and why the last one failed
+    git_sync_job: Option<mpsc::Receiver<Result<(), String>>>,
+    git_sync_error: Option<String>,
+    // Why the last save failed, cleared by the next one that works
+    save_error: Option<String>,
+    // Path typed in for a copy of the tasks while the data file can't be written
+    save_copy_path: Option<String>,
+    // Local HTTP API while it's switched on, and why it couldn't start
+    api: Option<api::ApiServer>,
+    api_error: Option<String>,
+    // The review reminder is showing
+    review_prompt: bool,
+    // A reminder came due while quiet and waits for quiet to end
+    review_queued: bool,
+    // Why the configured font could not be loaded
+    font_error: Option<String>,
     last_save: Instant,
-    last_deleted_tasks: Vec<Task>,
-    dragging_task: Option<usize>,
+    // Tasks as last written to the recovery file
+    last_recovery: String,
+    // Tasks from a recovery file newer than the data file, waiting for the user to decide
+    recovered_tasks: Option<Vec<Task>>,
+    // Problems found by a strict load, shown until dismissed
+    load_issues: Vec<Diagnostic>,
+    // Why the last save on close failed, while asking whether to retry
+    close_save_error: Option<String>,
+    quit_without_saving: bool,
+    // Task list as it was before the last undoable change
+    undo_snapshot: Option<Vec<Task>>,
+    // Shape of the list right after the undoable change, once the frame it was made in ends.
+    // Any later change that took no snapshot of its own makes the snapshot stale.
+    undo_taken_at: Option<(usize, Option<DateTime<Local>>)>,
+    // Task list as it was when the current priority edit began
+    priority_edit_snapshot: Option<Vec<Task>>,
+    // Tasks under a press and being dragged, and the row the drag would drop on
+    pressed_task: Option<Uuid>,
+    dragging_task: Option<Uuid>,
     drag_over_task: Option<usize>,
+    // Scroll over a priority box not yet turned into a whole step
+    priority_scroll: f32,
+    // Dock setting last applied to the window, to flip decorations only on change
+    applied_dock: Dock,
+    // Card being dragged on the board view
+    board_drag: Option<Uuid>,
+    // Re-sort held back while priorities are being scrolled, so the row stays put
+    resort_at: Option<Instant>,
+    // Task the current scroll gesture is changing, so the gesture is one undo step
+    scrolled_task: Option<Uuid>,
+    // Completion streak in days and the day it was counted on, read from the history while enabled
+    streak: Option<(NaiveDate, u32)>,
 }
 
 impl Default for MyApp {
@@ -35,11 +378,62 @@ impl Default for MyApp {
             tasks: Vec::new(),
             new_task_text: String::new(),
             new_task_priority: 1,
-            new_task_color: Color32::WHITE,
+            new_task_color: DEFAULT_TASK_COLOR,
+            new_task_color_chosen: false,
+            focus_new_task: false,
+            settings: Settings::default(),
+            show_settings: false,
+            filter: Filter::default(),
+            quick_open: None,
+            quick_open_choice: 0,
+            scroll_to_task: None,
+            page_rows: 0,
+            cursor: None,
+            merge_path: None,
+            merge_result: None,
+            merge_preview: None,
+            bundle_path: None,
+            bundle_include_history: true,
+            bundle_status: None,
+            bundle_pending: None,
+            compare_path: None,
+            compare_result: None,
+            confirm_delete: false,
+            confirm_complete_shown: false,
+            priority_shortcut: false,
+            pending_paste: None,
+            reminders_shown: Vec::new(),
+            last_deleted: None,
+            adding_in_bulk: false,
+            sort_pending: false,
+            toast: None,
+            git_sync_job: None,
+            git_sync_error: None,
+            save_error: None,
+            save_copy_path: None,
+            api: None,
+            api_error: None,
+            review_prompt: false,
+            review_queued: false,
+            font_error: None,
             last_save: Instant::now(),
-            last_deleted_tasks: Vec::new(),
+            last_recovery: String::new(),
+            recovered_tasks: None,
+            load_issues: Vec::new(),
+            close_save_error: None,
+            quit_without_saving: false,
+            undo_snapshot: None,
+            undo_taken_at: None,
+            priority_edit_snapshot: None,
+            pressed_task: None,
             dragging_task: None,
             drag_over_task: None,
+            priority_scroll: 0.0,
+            board_drag: None,
+            applied_dock: Dock::Floating,
+            resort_at: None,
+            scrolled_task: None,
+            streak: None,
         }
     }
 }
@@ -53,392 +447,4360 @@ fn array_from_color32(color: Color32) -> [u8; 4] {
     [color.r(), color.g(), color.b(), color.a()]
 }
 
-impl MyApp {
-    fn load_tasks() -> Vec<Task> {
-        if let Some(path) = get_data_path() {
-            if let Ok(data) = fs::read_to_string(path) {
-                if let Ok(tasks) = serde_json::from_str(&data) {
-                    return tasks;
-                }
-            }
+fn default_color() -> [u8; 4] {
+    array_from_color32(DEFAULT_TASK_COLOR)
+}
+
+// Only web addresses and files dropped onto the window (stored as absolute paths)
+// get an open button, so a typo can't launch some local program
+fn is_openable_link(link: &str) -> bool {
+    let web = ["http://", "https://"].iter().any(|scheme| {
+        link.strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+    });
+    web || Path::new(link).is_absolute()
+}
+
+// Move `t` of the way from `a` towards `b`, keeping the alpha of `a`
+fn mix_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color32::from_rgba_unmultiplied(
+        mix(a.r(), b.r()),
+        mix(a.g(), b.g()),
+        mix(a.b(), b.b()),
+        a.a(),
+    )
+}
+
+// Fill and outline for a task's row. State is layered over the user's color
+// lightly enough that the color stays recognizable.
+fn row_style(
+    task: &Task,
+    is_cursor: bool,
+    now: DateTime<Local>,
+    stale_after_days: u32,
+) -> (Color32, egui::Stroke) {
+    let mut fill = color32_from_array(task.color);
+    match task.status {
+        Status::Active => {}
+        Status::Waiting => fill = mix_color(fill, Color32::from_gray(160), 0.4),
+        Status::Done => fill = mix_color(fill, Color32::from_rgb(170, 220, 170), 0.25),
+    }
+    // Aged, paper-like tint for tasks that have lingered
+    if task.is_stale(stale_after_days, now) {
+        fill = mix_color(fill, Color32::from_rgb(205, 180, 135), 0.3);
+    }
+    // Flash from yellow back to the usual fill just after the task is added
+    if let Some(added) = task.added_at {
+        let t = added.elapsed().as_secs_f32() / ADDED_FLASH.as_secs_f32();
+        if t < 1.0 {
+            fill = mix_color(fill, Color32::from_rgb(255, 240, 120), 1.0 - t);
         }
-        Vec::new()
     }
+    let stroke = if task.selected {
+        egui::Stroke::new(3.0, Color32::YELLOW)
+    } else if is_cursor {
+        egui::Stroke::new(2.0, Color32::from_rgb(0, 150, 255))
+    } else if task.is_overdue(now) {
+        egui::Stroke::new(2.0, Color32::from_rgb(220, 40, 40))
+    } else if task.is_done() {
+        egui::Stroke::new(1.0, Color32::from_rgb(60, 140, 60))
+    } else {
+        egui::Stroke::new(1.0, Color32::BLACK)
+    };
+    (fill, stroke)
+}
 
-    fn persist_tasks(&self) {
-        if let Some(path) = get_data_path() {
-            if let Ok(serialized) = serde_json::to_string_pretty(&self.tasks) {
-                fs::write(path, serialized).ok();
+// Lines of a diff, with hover text for added, removed and modified tasks in that order
+fn show_diff(ui: &mut egui::Ui, diff: &diff::Diff, hovers: [&str; 3]) {
+    egui::ScrollArea::vertical()
+        .max_height(300.0)
+        .show(ui, |ui| {
+            let added = Color32::from_rgb(60, 160, 60);
+            let removed = Color32::from_rgb(200, 60, 60);
+            let modified = Color32::from_rgb(200, 150, 40);
+            for text in &diff.added {
+                ui.colored_label(added, format!("+ {}", text))
+                    .on_hover_text(hovers[0]);
             }
+            for text in &diff.removed {
+                ui.colored_label(removed, format!("− {}", text))
+                    .on_hover_text(hovers[1]);
+            }
+            for (text, fields) in &diff.modified {
+                ui.colored_label(modified, format!("~ {} ({})", text, fields.join(", ")))
+                    .on_hover_text(hovers[2]);
+            }
+        });
+}
+
+// Counts of what applying would change, with the lines behind them folded away
+fn show_preview(ui: &mut egui::Ui, diff: &diff::Diff, hovers: [&str; 3]) {
+    ui.label(diff.summary());
+    if !diff.is_empty() {
+        egui::CollapsingHeader::new("Show changes").show(ui, |ui| show_diff(ui, diff, hovers));
+    }
+}
+
+// Hover card for a row: the full text and every field that is set
+fn task_details(ui: &mut egui::Ui, task: &Task) {
+    ui.set_max_width(300.0);
+    ui.label(egui::RichText::new(&task.text).strong());
+    ui.label(format!("Priority: {}", task.shown_priority()));
+    if task.status != Status::Active {
+        ui.label(format!("Status: {}", task.status.title()));
+    }
+    if let Some(context) = &task.context {
+        ui.label(format!("Context: {}", context));
+    }
+    if let Some(due) = task.due {
+        let due = match task.due_time {
+            Some(time) => due.and_time(time).format("%A %Y-%m-%d %H:%M").to_string(),
+            None => due.format("%A %Y-%m-%d").to_string(),
+        };
+        ui.label(format!("Due: {}", due));
+    }
+    if task.progress > 0 {
+        ui.label(format!("Progress: {}%", task.progress.min(100)));
+    }
+    if let Some(link) = &task.link {
+        ui.label(format!("Link: {}", link));
+    }
+    if let Some(created) = task.created {
+        ui.weak(format!("Created {}", created.format("%Y-%m-%d %H:%M")));
+    }
+}
+
+// Offered in the context picker even before any task uses them
+const CONTEXT_SUGGESTIONS: [&str; 4] = ["@home", "@computer", "@errands", "@phone"];
+
+// Contexts in use, sorted and without repeats
+fn contexts_in_use(tasks: &[Task]) -> Vec<String> {
+    let contexts: BTreeSet<&String> = tasks.iter().filter_map(|t| t.context.as_ref()).collect();
+    contexts.into_iter().cloned().collect()
+}
+
+// Typed context, trimmed and starting with @
+fn normalize_context(typed: &str) -> Option<String> {
+    let typed = typed.trim().trim_start_matches('@').trim();
+    (!typed.is_empty()).then(|| format!("@{}", typed))
+}
+
+// Context menu picker for a task's context, from the known ones or typed in
+fn gtd_context_menu(ui: &mut egui::Ui, task: &mut Task, known: &[String]) {
+    let mut set = None;
+    for context in known {
+        if ui
+            .selectable_label(task.context.as_ref() == Some(context), context)
+            .clicked()
+        {
+            set = Some(Some(context.clone()));
         }
     }
 
-    fn add_task(&mut self) {
-        if self.new_task_text.trim().is_empty() {
-            return;
+    // Typed text is kept per task until Enter adds it
+    let draft_id = egui::Id::new(("context_draft", task.id));
+    let mut draft = ui
+        .data_mut(|d| d.get_temp::<String>(draft_id))
+        .unwrap_or_default();
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut draft)
+            .hint_text("@new context")
+            .desired_width(120.0),
+    );
+    if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+        if let Some(context) = normalize_context(&draft) {
+            set = Some(Some(context));
         }
+    }
+    ui.data_mut(|d| d.insert_temp(draft_id, draft));
 
-        self.tasks.push(Task {
-            text: self.new_task_text.trim().to_string(),
-            priority: self.new_task_priority,
-            color: array_from_color32(self.new_task_color),
-            selected: false,
-            editing: false,
-            editing_priority: false,
+    if ui
+        .add_enabled(task.context.is_some(), egui::Button::new("No context"))
+        .clicked()
+    {
+        set = Some(None);
+    }
+
+    if let Some(context) = set {
+        task.context = context;
+        task.touch();
+        ui.data_mut(|d| d.remove::<String>(draft_id));
+        ui.close_menu();
+    }
+}
+
+// Context menu editor for a task's reminder time
+fn reminder_menu(ui: &mut egui::Ui, task: &mut Task) {
+    // Typed text is kept per task until it parses
+    let draft_id = egui::Id::new(("remind_draft", task.id));
+    let mut draft = ui
+        .data_mut(|d| d.get_temp::<String>(draft_id))
+        .unwrap_or_else(|| {
+            task.remind_at
+                .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
         });
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut draft)
+            .hint_text("YYYY-MM-DD HH:MM")
+            .desired_width(150.0),
+    );
+    let parsed = chrono::NaiveDateTime::parse_from_str(draft.trim(), "%Y-%m-%d %H:%M")
+        .ok()
+        .and_then(|at| Local.from_local_datetime(&at).earliest());
+    let mut set = None;
+    if response.changed() {
+        if let Some(at) = parsed {
+            set = Some(Some(at));
+        }
+    }
+    if parsed.is_none() && !draft.trim().is_empty() {
+        ui.weak("Not a date and time");
+    }
+    ui.data_mut(|d| d.insert_temp(draft_id, draft));
 
-        // Sort tasks by priority descending (higher priority first)
-        self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+    ui.horizontal(|ui| {
+        let now = Local::now();
+        if ui.button("In 1 hour").clicked() {
+            set = Some(Some(now + chrono::Duration::hours(1)));
+        }
+        let tomorrow_nine = now
+            .date_naive()
+            .succ_opt()
+            .and_then(|day| day.and_hms_opt(9, 0, 0))
+            .and_then(|at| Local.from_local_datetime(&at).earliest());
+        if let Some(at) = tomorrow_nine {
+            if ui.button("Tomorrow 9:00").clicked() {
+                set = Some(Some(at));
+            }
+        }
+    });
+    if ui
+        .add_enabled(task.remind_at.is_some(), egui::Button::new("No reminder"))
+        .clicked()
+    {
+        set = Some(None);
+    }
 
-        self.new_task_text.clear();
+    if let Some(remind_at) = set {
+        task.remind_at = remind_at;
+        task.reminded = false;
+        task.touch();
+        if !response.changed() {
+            ui.data_mut(|d| d.remove::<String>(draft_id));
+            ui.close_menu();
+        }
     }
 }
 
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
-        let now = Instant::now();
-        if now.duration_since(self.last_save).as_secs() > 30 {
-            self.persist_tasks();
-            self.last_save = now;
+// Context menu editor for a task's due date and optional time
+fn due_date_menu(ui: &mut egui::Ui, task: &mut Task) {
+    // Typed text is kept per task until it parses
+    let draft_id = egui::Id::new(("due_draft", task.id));
+    let mut draft = ui
+        .data_mut(|d| d.get_temp::<String>(draft_id))
+        .unwrap_or_else(|| task.due.map(|d| d.to_string()).unwrap_or_default());
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut draft)
+            .hint_text("YYYY-MM-DD")
+            .desired_width(120.0),
+    );
+    let parsed = NaiveDate::parse_from_str(draft.trim(), "%Y-%m-%d");
+    if response.changed() {
+        if let Ok(date) = parsed {
+            task.due = Some(date);
+            task.touch();
         }
+    }
+    if parsed.is_err() && !draft.trim().is_empty() {
+        ui.weak("Not a date");
+    }
+    ui.data_mut(|d| d.insert_temp(draft_id, draft));
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let text = "📋 Tasks";
-            let font_id = egui::FontId::proportional(32.0);
-
-            // Draw shadow
-            ui.painter().text(
-                ui.min_rect().center_top() + egui::vec2(2.0, 2.0),
-                egui::Align2::CENTER_TOP,
-                text,
+    let time_id = egui::Id::new(("due_time_draft", task.id));
+    let mut time_draft = ui
+        .data_mut(|d| d.get_temp::<String>(time_id))
+        .unwrap_or_else(|| {
+            task.due_time
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_default()
+        });
+    let response = ui.add_enabled(
+        task.due.is_some(),
+        egui::TextEdit::singleline(&mut time_draft)
+            .hint_text("HH:MM, or end of day")
+            .desired_width(120.0),
+    );
+    let typed = time_draft.trim();
+    let parsed = NaiveTime::parse_from_str(typed, "%H:%M");
+    if response.changed() {
+        if typed.is_empty() {
+            task.due_time = None;
+            task.touch();
+        } else if let Ok(time) = parsed {
+            task.due_time = Some(time);
+            task.touch();
+        }
+    }
+    if parsed.is_err() && !typed.is_empty() {
+        ui.weak("Not a time");
+    }
+    ui.data_mut(|d| d.insert_temp(time_id, time_draft));
+
+    ui.horizontal(|ui| {
+        let today = Local::now().date_naive();
+        for (label, date) in [
+            ("Today", today),
+            ("Tomorrow", today.succ_opt().unwrap_or(today)),
+        ] {
+            if ui.button(label).clicked() {
+                task.due = Some(date);
+                task.touch();
+                ui.data_mut(|d| d.remove::<String>(draft_id));
+                ui.close_menu();
+            }
+        }
+    });
+    if ui
+        .add_enabled(task.due.is_some(), egui::Button::new("No due date"))
+        .clicked()
+    {
+        task.due = None;
+        task.due_time = None;
+        task.touch();
+        ui.data_mut(|d| {
+            d.remove::<String>(draft_id);
+            d.remove::<String>(time_id);
+        });
+        ui.close_menu();
+    }
+}
+
+// Right-click menu for a task row. Returns whether the task was picked as a
+// template for the add row.
+fn task_context_menu(
+    ui: &mut egui::Ui,
+    task: &mut Task,
+    clipped: bool,
+    known_contexts: &[String],
+) -> bool {
+    let mut use_as_template = false;
+    if ui.button("Use as template").clicked() {
+        use_as_template = true;
+        ui.close_menu();
+    }
+    let waiting = task.status == Status::Waiting;
+    let label = if waiting {
+        "No longer waiting"
+    } else {
+        "⏳ Waiting on someone"
+    };
+    if ui.button(label).clicked() {
+        task.status = if waiting {
+            Status::Active
+        } else {
+            Status::Waiting
+        };
+        task.touch();
+        ui.close_menu();
+    }
+    if clipped {
+        let label = if task.expanded {
+            "Show less"
+        } else {
+            "Show full text"
+        };
+        if ui.button(label).clicked() {
+            task.expanded = !task.expanded;
+            ui.close_menu();
+        }
+    }
+    ui.menu_button("Icon", |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.set_max_width(160.0);
+            for icon in TASK_ICONS {
+                if ui.button(icon).clicked() {
+                    task.icon = Some(icon.to_string());
+                    task.touch();
+                    ui.close_menu();
+                }
+            }
+        });
+        let mut typed = task.icon.clone().unwrap_or_default();
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut typed)
+                    .hint_text("Type an icon")
+                    .desired_width(100.0),
+            )
+            .changed()
+        {
+            // Keep it to a few characters so it stays a prefix
+            let typed: String = typed.trim().chars().take(4).collect();
+            task.icon = (!typed.is_empty()).then_some(typed);
+            task.touch();
+        }
+        if ui
+            .add_enabled(task.icon.is_some(), egui::Button::new("No icon"))
+            .clicked()
+        {
+            task.icon = None;
+            task.touch();
+            ui.close_menu();
+        }
+    });
+    ui.menu_button("Progress", |ui| {
+        let response = ui.add(
+            egui::Slider::new(&mut task.progress, 0..=100)
+                .step_by(5.0)
+                .suffix("%"),
+        );
+        if response.changed() {
+            task.touch();
+        }
+    });
+    ui.menu_button("Due date", |ui| due_date_menu(ui, task));
+    ui.menu_button("Reminder", |ui| reminder_menu(ui, task));
+    ui.menu_button("Context", |ui| gtd_context_menu(ui, task, known_contexts));
+    ui.menu_button("Link", |ui| {
+        let mut typed = task.link.clone().unwrap_or_default();
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut typed)
+                    .hint_text("https://…")
+                    .desired_width(200.0),
+            )
+            .changed()
+        {
+            let typed = typed.trim();
+            task.link = (!typed.is_empty()).then(|| typed.to_string());
+            task.touch();
+        }
+        if task.link.as_deref().is_some_and(|l| !is_openable_link(l)) {
+            ui.weak("Not a web address");
+        }
+        if ui
+            .add_enabled(task.link.is_some(), egui::Button::new("No link"))
+            .clicked()
+        {
+            task.link = None;
+            task.touch();
+            ui.close_menu();
+        }
+    });
+    use_as_template
+}
+
+// Settings the task rows are drawn with, read once per frame
+struct RowOptions<'a> {
+    palette: &'static palette::Palette,
+    priority_display: PriorityDisplay,
+    show_bar_number: bool,
+    priority_side: Side,
+    checkbox_side: Side,
+    show_row_numbers: bool,
+    max_lines: usize,
+    text_cleanup: TextCleanup,
+    stale_after_days: u32,
+    task_font: egui::FontId,
+    known_contexts: &'a [String],
+    now: DateTime<Local>,
+    // No hover cards while a task is being dragged
+    dragging: bool,
+}
+
+// What the user did to a row this frame, applied once the whole list is drawn
+#[derive(Default)]
+struct RowActions {
+    clicked: bool,
+    drag_started: bool,
+    // The pointer has moved past DRAG_THRESHOLD since the press
+    dragged_far: bool,
+    move_to: Option<usize>,
+    use_as_template: bool,
+    done_toggled: bool,
+    priority_edit_started: bool,
+    priority_changed: bool,
+    priority_scroll: Option<f32>,
+}
+
+fn done_checkbox(ui: &mut egui::Ui, task: &Task, actions: &mut RowActions) {
+    let mut done = task.is_done();
+    let response = ui.checkbox(&mut done, "");
+    response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::Checkbox, done, "Done"));
+    if response.changed() {
+        actions.done_toggled = true;
+    }
+}
+
+// Priority box with editing support, or a fill bar when
+// that display is chosen and the priority isn't being edited
+fn priority_box(
+    ui: &mut egui::Ui,
+    task: &mut Task,
+    options: &RowOptions,
+    actions: &mut RowActions,
+) {
+    let palette = options.palette;
+    let response = if options.priority_display == PriorityDisplay::Bar && !task.editing_priority {
+        let (rect, response) = ui.allocate_exact_size(Vec2::new(40.0, 14.0), egui::Sense::click());
+        let fraction = task.priority.clamp(1.0, 10.0) / 10.0;
+        let fill =
+            egui::Rect::from_min_size(rect.min, Vec2::new(rect.width() * fraction, rect.height()));
+        let painter = ui.painter();
+        painter.rect_filled(
+            rect,
+            egui::Rounding::same(3.0),
+            Color32::from_black_alpha(60),
+        );
+        painter.rect_filled(
+            fill,
+            egui::Rounding::same(3.0),
+            palette.priority_color(task.priority),
+        );
+        painter.rect_stroke(
+            rect,
+            egui::Rounding::same(3.0),
+            egui::Stroke::new(1.0, Color32::BLACK),
+        );
+        if options.show_bar_number {
+            let text = task.shown_priority().to_string();
+            let font_id = egui::FontId::proportional(11.0);
+            painter.text(
+                rect.center() + egui::vec2(1.0, 1.0),
+                egui::Align2::CENTER_CENTER,
+                &text,
                 font_id.clone(),
-                Color32::from_rgba_unmultiplied(0, 0, 0, 150),
+                Color32::BLACK,
             );
-
-            // Draw main colored text
-            ui.painter().text(
-                ui.min_rect().center_top(),
-                egui::Align2::CENTER_TOP,
-                text,
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                &text,
                 font_id,
-                Color32::from_rgb(0, 150, 255),
+                Color32::WHITE,
             );
+        }
 
-            ui.add_space(50.0);
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Button,
+                format!("Priority {}", task.shown_priority()),
+            )
+        });
+        if response.double_clicked() {
+            actions.priority_edit_started = true;
+        }
+        response
+    } else {
+        egui::Frame::none()
+            .fill(Color32::BLACK)
+            .stroke(egui::Stroke::new(
+                1.0,
+                palette.priority_color(task.priority),
+            ))
+            .rounding(egui::Rounding::same(6.0))
+            .inner_margin(egui::Margin {
+                left: 2.0,
+                right: 2.0,
+                top: 4.0,
+                bottom: 2.0,
+            })
+            .show(ui, |ui| {
+                let priority_size = Vec2::new(32.0, 24.0);
+                ui.allocate_ui(priority_size, |ui| {
+                    ui.centered_and_justified(|ui| {
+                        if task.editing_priority {
+                            let response = ui.add(
+                                egui::DragValue::from_get_set(|value| {
+                                    if let Some(value) = value {
+                                        task.priority = value as f32;
+                                        task.touch();
+                                    }
+                                    task.priority.round() as f64
+                                })
+                                .clamp_range(1..=10)
+                                .speed(1)
+                                .max_decimals(0),
+                            );
+                            if response.lost_focus() || ui.input(|i| i.key_pressed(Key::Enter)) {
+                                task.editing_priority = false;
+                                actions.priority_changed = true;
+                            }
+                        } else {
+                            let response = ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(task.shown_priority().to_string())
+                                        .color(Color32::WHITE)
+                                        .size(14.0),
+                                )
+                                .sense(egui::Sense::click()),
+                            );
+                            response.widget_info(|| {
+                                egui::WidgetInfo::labeled(
+                                    egui::WidgetType::Button,
+                                    format!("Priority {}", task.shown_priority()),
+                                )
+                            });
+                            if response.double_clicked() {
+                                actions.priority_edit_started = true;
+                            }
+                        }
+                    });
+                });
+            })
+            .response
+    };
 
-            ui.horizontal(|ui| {
-                ui.label("Task:");
-                ui.text_edit_singleline(&mut self.new_task_text);
-
-                ui.label("Priority:");
-                ui.add(
-                    egui::DragValue::new(&mut self.new_task_priority)
-                        .clamp_range(1..=10)
-                        .speed(1),
-                );
+    // Shift+scrolling over the priority box nudges the priority. egui-winit turns a
+    // shifted wheel into horizontal scroll, which the vertical list ignores, so
+    // plain scrolling moves the list without touching priorities.
+    let response = response.on_hover_text("Shift+scroll to change the priority");
+    if response.hovered() && !task.editing_priority {
+        let scroll = ui.input(|i| {
+            if i.modifiers.shift {
+                i.scroll_delta.x + i.scroll_delta.y
+            } else {
+                0.0
+            }
+        });
+        if scroll != 0.0 {
+            actions.priority_scroll = Some(scroll);
+        }
+    }
+}
 
-                let mut color_arr = array_from_color32(self.new_task_color);
-                ui.color_edit_button_srgba_unmultiplied(&mut color_arr);
-                self.new_task_color = color32_from_array(color_arr);
+// The task's text, as an editor while editing and otherwise as wrapped text that
+// can be clicked, dragged, double-clicked and right-clicked
+fn task_text(ui: &mut egui::Ui, task: &mut Task, options: &RowOptions, actions: &mut RowActions) {
+    let available_width = ui.available_width();
+    let font_id = options.task_font.clone();
 
-                if ui.button("➕ Add").clicked() {
-                    self.add_task();
-                }
+    if task.editing {
+        // Counter to the right of the editor, warning once the text runs long
+        let chars = task.text.chars().count();
+        let words = task.text.split_whitespace().count();
+        let long = chars > LONG_TASK_CHARS;
+        let counter = egui::RichText::new(format!("{} words, {} chars", words, chars))
+            .small()
+            .color(if long {
+                Color32::from_rgb(220, 120, 0)
+            } else {
+                Color32::GRAY
             });
+        let counter = ui.label(counter);
+        if long {
+            counter.on_hover_text("Long for one task; it may read better split into several");
+        }
+        let response = ui.add_sized(
+            Vec2::new(ui.available_width(), 30.0),
+            egui::TextEdit::singleline(&mut task.text)
+                .font(font_id)
+                .desired_width(f32::INFINITY),
+        );
+
+        if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            task.finish_editing(options.text_cleanup);
+        }
+        return;
+    }
+
+    let padding = 12.0;
+    let text_width = available_width - padding;
+
+    // Layout job to measure wrapped text height, struck through once done
+    let mut shown_text = match &task.icon {
+        Some(icon) => format!("{} {}", icon, task.text),
+        None => task.text.clone(),
+    };
+    if task.status == Status::Waiting {
+        shown_text = format!("⏳ {}", shown_text);
+    }
+    let mut job =
+        egui::text::LayoutJob::simple(shown_text.clone(), font_id, Color32::BLACK, text_width);
+    if task.is_done() {
+        for section in &mut job.sections {
+            section.format.color = Color32::from_gray(90);
+            section.format.strikethrough = egui::Stroke::new(1.5, Color32::from_gray(90));
+        }
+    }
+    let mut galley = ui.fonts(|f| f.layout_job(job.clone()));
+
+    // Clip long tasks to the line cap with an ellipsis unless expanded
+    let max_lines = options.max_lines;
+    let clipped = max_lines > 0 && galley.rows.len() > max_lines;
+    if clipped && !task.expanded {
+        job.wrap.max_rows = max_lines;
+        galley = ui.fonts(|f| f.layout_job(job));
+    }
+    let text_height = galley.size().y;
+    let block_height = text_height + padding;
+
+    // Allocate a draggable and clickable response for the task text area
+    let response = ui.allocate_response(
+        Vec2::new(available_width, block_height),
+        egui::Sense::click_and_drag(),
+    );
+
+    // Announce the painted text to assistive tech
+    response.widget_info(|| {
+        egui::WidgetInfo::selected(
+            egui::WidgetType::Button,
+            task.selected,
+            format!(
+                "{}, priority {}{}",
+                task.text,
+                task.shown_priority(),
+                match task.status {
+                    Status::Active => "",
+                    Status::Waiting => ", waiting",
+                    Status::Done => ", done",
+                }
+            ),
+        )
+    });
 
-            ui.add_space(12.0);
+    // Draw the wrapped text as a read-only editor so a drag within it selects
+    // text for copying, while a drag from the rest of the row still reorders
+    let text_rect = egui::Rect::from_min_size(
+        response.rect.left_top() + egui::vec2(6.0, 6.0),
+        galley.size(),
+    );
+    let mut layouter = |_: &egui::Ui, _: &str, _: f32| galley.clone();
+    let text_response = ui.put(
+        text_rect,
+        egui::TextEdit::multiline(&mut shown_text.as_str())
+            .id(egui::Id::new(("task_text", task.id)))
+            .frame(false)
+            .margin(Vec2::ZERO)
+            .desired_rows(1)
+            .desired_width(text_rect.width())
+            .layouter(&mut layouter),
+    );
 
-            // Color presets
+    // Editing toggle on double-click
+    if response.double_clicked() || text_response.double_clicked() {
+        task.start_editing();
+    }
+
+    // Selection toggle on click; a plain click hands focus back so the
+    // keyboard shortcuts keep working
+    if response.clicked() || text_response.clicked() {
+        text_response.surrender_focus();
+        task.selected = !task.selected;
+        actions.clicked = true;
+    }
+
+    // Drag handling: only start dragging once the pointer has
+    // moved past the threshold, then track the drop target
+    actions.drag_started = response.drag_started();
+    if response.dragged() {
+        let moved = ui.input(
+            |i| match (i.pointer.press_origin(), i.pointer.interact_pos()) {
+                (Some(origin), Some(pos)) => origin.distance(pos),
+                _ => 0.0,
+            },
+        );
+        actions.dragged_far = moved > DRAG_THRESHOLD;
+    }
+
+    let response = (response | text_response).context_menu(|ui| {
+        if task_context_menu(ui, task, clipped, options.known_contexts) {
+            actions.use_as_template = true;
+        }
+    });
+
+    // Full details on hover, kept out of the way while dragging
+    if !options.dragging {
+        response.on_hover_ui(|ui| task_details(ui, task));
+    }
+}
+
+// One task's row: its number, move buttons, checkbox, flag, priority, badges and
+// text. `above` and `below` are the neighbouring shown rows the move buttons go to.
+fn show_task_row(
+    ui: &mut egui::Ui,
+    task: &mut Task,
+    number: usize,
+    (above, below): (Option<usize>, Option<usize>),
+    is_cursor: bool,
+    options: &RowOptions,
+) -> (egui::Response, RowActions) {
+    let mut actions = RowActions::default();
+    let now = options.now;
+    let (fill, stroke) = row_style(task, is_cursor, now, options.stale_after_days);
+    let row = egui::Frame::none()
+        .fill(fill)
+        .stroke(stroke)
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin {
+            left: 6.0,
+            right: 6.0,
+            top: 6.0,
+            bottom: 6.0,
+        })
+        .show(ui, |ui| {
             ui.horizontal(|ui| {
-                let presets = [
-                    Color32::LIGHT_GREEN,
-                    Color32::LIGHT_YELLOW,
-                    Color32::LIGHT_RED,
-                    Color32::LIGHT_BLUE,
-                    Color32::WHITE,
-                    Color32::DARK_RED,
-                    Color32::DARK_GREEN,
-                ];
-                for &color in &presets {
+                // Position among the shown rows, to point tasks out by number
+                if options.show_row_numbers {
+                    ui.add_sized(
+                        Vec2::new(22.0, 20.0),
+                        egui::Label::new(
+                            egui::RichText::new(format!("{}.", number))
+                                .monospace()
+                                .color(Color32::from_gray(70)),
+                        ),
+                    );
+                }
+                // Up/down buttons, an alternative to dragging
+                ui.vertical(|ui| {
+                    ui.spacing_mut().item_spacing.y = 0.0;
                     if ui
-                        .add(
-                            egui::Button::new("   ")
-                                .fill(color)
-                                .frame(true)
-                                .min_size(Vec2::new(24.0, 24.0)),
-                        )
+                        .add_enabled(above.is_some(), egui::Button::new("⏶").small())
+                        .on_hover_text("Move up")
                         .clicked()
                     {
-                        for task in self.tasks.iter_mut().filter(|t| t.selected) {
-                            task.color = [color.r(), color.g(), color.b(), color.a()];
-                        }
-                        self.new_task_color = color;
+                        actions.move_to = above;
+                    }
+                    if ui
+                        .add_enabled(below.is_some(), egui::Button::new("⏷").small())
+                        .on_hover_text("Move down")
+                        .clicked()
+                    {
+                        actions.move_to = below;
                     }
+                });
+
+                if options.checkbox_side == Side::Left {
+                    done_checkbox(ui, task, &mut actions);
                 }
-            });
 
-            ui.add_space(16.0);
+                let (star, hint) = if task.flagged {
+                    ("★", "Unflag")
+                } else {
+                    ("☆", "Flag")
+                };
+                if ui
+                    .add(egui::Button::new(star).frame(false))
+                    .on_hover_text(hint)
+                    .clicked()
+                {
+                    task.flagged = !task.flagged;
+                    task.touch();
+                }
 
-            // Keyboard navigation
-            if !ctx.wants_keyboard_input() {
-                let selected_idx = self.tasks.iter().position(|t| t.selected);
+                ui.add_space(6.0);
 
-                if ui.input(|i| i.key_pressed(Key::J)) {
-                    if let Some(i) = selected_idx {
-                        if i + 1 < self.tasks.len() {
-                            self.tasks[i].selected = false;
-                            self.tasks[i + 1].selected = true;
-                        }
-                    } else if !self.tasks.is_empty() {
-                        self.tasks[0].selected = true;
-                    }
+                let show_priority = options.priority_display != PriorityDisplay::Hidden;
+                if show_priority && options.priority_side == Side::Left {
+                    priority_box(ui, task, options, &mut actions);
+                    ui.add_space(10.0);
                 }
 
-                if ui.input(|i| i.key_pressed(Key::K)) {
-                    if let Some(i) = selected_idx {
-                        if i > 0 {
-                            self.tasks[i].selected = false;
-                            self.tasks[i - 1].selected = true;
+                // Right-hand controls are laid out first so the text fills the rest
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if options.checkbox_side == Side::Right {
+                        done_checkbox(ui, task, &mut actions);
+                    }
+                    if show_priority && options.priority_side == Side::Right {
+                        priority_box(ui, task, options, &mut actions);
+                        ui.add_space(10.0);
+                    }
+                    if let (Some(label), Some(due)) = (task.due_label(now), task.due_at()) {
+                        let hover = if task.due_time.is_some() {
+                            due.format("Due %A %Y-%m-%d %H:%M")
+                        } else {
+                            due.format("Due %A %Y-%m-%d")
+                        };
+                        ui.weak(label).on_hover_text(hover.to_string());
+                    }
+                    if let Some(context) = &task.context {
+                        ui.label(
+                            egui::RichText::new(context)
+                                .small()
+                                .color(Color32::from_gray(40))
+                                .background_color(Color32::from_black_alpha(25)),
+                        );
+                    }
+                    if task.is_stale(options.stale_after_days, now) {
+                        let age = task.age_days(now).unwrap_or_default();
+                        ui.weak(format!("{} days old", age));
+                    }
+                    if let Some(at) = task.remind_at.filter(|_| !task.reminded) {
+                        ui.weak("🔔")
+                            .on_hover_text(at.format("Reminder %A %Y-%m-%d %H:%M").to_string());
+                    }
+                    if let Some(link) = &task.link {
+                        let response = ui
+                            .add_enabled(
+                                is_openable_link(link),
+                                egui::Button::new("🔗").frame(false),
+                            )
+                            .on_hover_text(link)
+                            .on_disabled_hover_text("Not a web address");
+                        if response.clicked() {
+                            open::that(link).ok();
                         }
-                    } else if !self.tasks.is_empty() {
-                        self.tasks[0].selected = true;
                     }
-                }
 
-                if ui.input(|i| i.key_pressed(Key::D)) {
-                    self.last_deleted_tasks =
-                        self.tasks.iter().filter(|t| t.selected).cloned().collect();
-                    self.tasks.retain(|t| !t.selected);
-                }
+                    task_text(ui, task, options, &mut actions);
+                });
+            });
+        });
+    (row.response, actions)
+}
 
-                if ui.input(|i| i.key_pressed(Key::U)) {
-                    if !self.last_deleted_tasks.is_empty() {
-                        self.tasks.append(&mut self.last_deleted_tasks);
-                        self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
-                        self.last_deleted_tasks.clear();
-                    }
+// Bring tasks read from older files up to date
+fn migrate_tasks(tasks: &mut [Task]) {
+    // A hand-copied entry can repeat an id; keep the first one as is
+    let mut seen = std::collections::HashSet::new();
+    for task in tasks.iter_mut() {
+        if !seen.insert(task.id) {
+            task.id = Uuid::new_v4();
+        }
+    }
+    for task in tasks.iter_mut().filter(|t| t.legacy_done) {
+        task.status = Status::Done;
+        task.legacy_done = false;
+    }
+    spread_tied_priorities(tasks);
+}
+
+// Files from before fractional priorities only hold whole numbers, so tasks sharing a
+// priority are spread just around it, keeping their order and their shown priority
+fn spread_tied_priorities(tasks: &mut [Task]) {
+    if tasks.iter().any(|t| t.priority.fract() != 0.0) {
+        return;
+    }
+    for priority in 1..=10 {
+        let tied: Vec<usize> = (0..tasks.len())
+            .filter(|&i| tasks[i].priority == priority as f32)
+            .collect();
+        let n = tied.len() as f32;
+        for (k, &i) in tied.iter().enumerate() {
+            let k = k as f32;
+            // At the bounds the spread only goes inward, and stays within half a step
+            // so it can't pass the neighbouring priority's spread
+            tasks[i].priority = match priority {
+                10 => 10.0 - 0.5 * k / n,
+                1 => 1.0 + 0.5 * (n - 1.0 - k) / n,
+                _ => priority as f32 + 0.4 - 0.8 * (k + 1.0) / (n + 1.0),
+            };
+        }
+    }
+}
+
+impl MyApp {
+    fn load_tasks(settings: &Settings) -> Vec<Task> {
+        if let Some(path) = get_data_path(settings) {
+            if let Ok(data) = fs::read_to_string(path) {
+                if let Ok(mut tasks) = serde_json::from_str::<Vec<Task>>(&data) {
+                    migrate_tasks(&mut tasks);
+                    return tasks;
                 }
             }
+        }
+        Vec::new()
+    }
 
-            // Show tasks
-            let mut priority_changed = false;
+    // Load task by task, moving entries with problems to the quarantine file
+    fn load_tasks_strict(settings: &Settings) -> (Vec<Task>, Vec<Diagnostic>) {
+        let Some(data) = get_data_path(settings).and_then(|path| fs::read_to_string(path).ok())
+        else {
+            return (Vec::new(), Vec::new());
+        };
 
-            for (i, task) in self.tasks.iter_mut().enumerate() {
-                egui::Frame::none()
-                    .fill(color32_from_array(task.color))
-                    .stroke(if task.selected {
-                        egui::Stroke::new(3.0, Color32::YELLOW)
-                    } else {
-                        egui::Stroke::new(1.0, Color32::BLACK)
-                    })
-                    .rounding(egui::Rounding::same(8.0))
-                    .inner_margin(egui::Margin {
-                        left: 6.0,
-                        right: 6.0,
-                        top: 6.0,
-                        bottom: 6.0,
-                    })
-                    .show(ui, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.add_space(6.0);
+        let mut loaded = validation::load_strict(&data);
+        migrate_tasks(&mut loaded.tasks);
+        if !loaded.quarantined.is_empty() {
+            if let Some(path) = get_quarantine_path() {
+                // Add to anything quarantined earlier rather than replacing it
+                let mut quarantined: Vec<serde_json::Value> = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|data| serde_json::from_str(&data).ok())
+                    .unwrap_or_default();
+                let moved = loaded.quarantined.len();
+                quarantined.extend(loaded.quarantined);
+                if let Ok(serialized) = serde_json::to_string_pretty(&quarantined) {
+                    fs::write(&path, serialized).ok();
+                }
+
+                let mut diagnostics = loaded.diagnostics;
+                diagnostics.push(Diagnostic {
+                    entry: None,
+                    problem: format!("{} entries were moved to {}", moved, path.display()),
+                });
+                return (loaded.tasks, diagnostics);
+            }
+        }
+
+        (loaded.tasks, loaded.diagnostics)
+    }
+
+    fn persist_tasks(&self) -> io::Result<()> {
+        let path = get_data_path(&self.settings)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+        let serialized = serde_json::to_string_pretty(&self.tasks)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serialized)?;
+        Ok(())
+    }
+
+    // Save from the widget. Once the real file is saved the recovery copy is no longer
+    // needed, unless the user still has to decide whether to restore it. Headless saves
+    // (capture, stdin import) leave it alone, since only the widget offers it back.
+    fn persist_from_ui(&self) -> io::Result<()> {
+        self.persist_tasks()?;
+        if self.recovered_tasks.is_none() {
+            if let Some(recovery) = get_recovery_path() {
+                fs::remove_file(recovery).ok();
+            }
+        }
+        Ok(())
+    }
+
+    // Save from the UI, keeping any failure on screen until a later save works
+    fn save_tasks(&mut self) -> bool {
+        match self.persist_from_ui() {
+            Ok(()) => {
+                self.save_error = None;
+                true
+            }
+            Err(err) => {
+                self.save_error = Some(err.to_string());
+                false
+            }
+        }
+    }
+
+    // Tasks from the recovery file, if it was written after the last normal save
+    fn load_recovery(settings: &Settings) -> Option<Vec<Task>> {
+        let recovery = get_recovery_path()?;
+        let recovery_modified = fs::metadata(&recovery).and_then(|m| m.modified()).ok()?;
+        let data_modified =
+            get_data_path(settings).and_then(|p| fs::metadata(p).and_then(|m| m.modified()).ok());
+
+        if data_modified.is_none_or(|data| recovery_modified > data) {
+            let data = fs::read_to_string(recovery).ok()?;
+            let mut tasks: Vec<Task> = serde_json::from_str(&data).ok()?;
+            migrate_tasks(&mut tasks);
+            Some(tasks)
+        } else {
+            None
+        }
+    }
+
+    // Reread the streak when turned on, after a completion or on a new day,
+    // cheering when it reaches a milestone
+    fn update_streak(&mut self, ctx: &Context) {
+        let logged = history::take_logged() > 0;
+        if !self.settings.streak_enabled {
+            self.streak = None;
+            return;
+        }
+        let today = Local::now().date_naive();
+        if self.streak.is_some_and(|(day, _)| day == today) && !logged {
+            return;
+        }
+        let streak = history::streak(today);
+        if self.streak.is_some_and(|(_, before)| before < streak)
+            && STREAK_MILESTONES.contains(&streak)
+        {
+            self.toast = Some((
+                format!("🎉 {} days in a row — keep it going!", streak),
+                Instant::now(),
+            ));
+            ctx.request_repaint();
+        }
+        self.streak = Some((today, streak));
+    }
+
+    // Mirror the tasks to the recovery file whenever they change
+    fn write_recovery(&mut self) {
+        if self.recovered_tasks.is_some() {
+            return;
+        }
+        if let Ok(serialized) = serde_json::to_string(&self.tasks) {
+            if serialized != self.last_recovery {
+                if let Some(path) = get_recovery_path() {
+                    if let Some(dir) = path.parent() {
+                        fs::create_dir_all(dir).ok();
+                    }
+                    fs::write(path, &serialized).ok();
+                }
+                self.last_recovery = serialized;
+            }
+        }
+    }
+
+    fn add_task(&mut self) {
+        if self.new_task_text.trim().is_empty() {
+            return;
+        }
+        let text = self.settings.text_cleanup.apply(&self.new_task_text);
+        self.insert_task(text, self.new_task_priority, self.new_task_color());
+        self.new_task_text.clear();
+        self.reset_new_task_priority();
+    }
+
+    // Tasks entered one after another are sorted once the box is left
+    fn add_from_row(&mut self, entered: bool) {
+        self.adding_in_bulk = entered && self.settings.defer_sort_while_adding;
+        self.add_task();
+        self.adding_in_bulk = false;
+    }
+
+    // Run the sort held back while adding, if there is one
+    fn end_bulk_add(&mut self) {
+        if self.sort_pending {
+            self.sort_pending = false;
+            self.sort_tasks();
+        }
+    }
+
+    // Back to the configured default after an add, unless the last one is kept
+    fn reset_new_task_priority(&mut self) {
+        if !self.settings.remember_last_priority {
+            self.new_task_priority = self.settings.default_priority;
+        }
+    }
+
+    // Add and save the tasks other processes left in the inbox, at the default priority
+    fn take_inbox(&mut self) {
+        let texts = inbox::take();
+        if texts.is_empty() {
+            return;
+        }
+        self.snapshot();
+        let priority = self.settings.default_priority;
+        let color = self
+            .settings
+            .priority_color(priority)
+            .map_or(DEFAULT_TASK_COLOR, color32_from_array);
+        for text in &texts {
+            let text = self.settings.text_cleanup.apply(text);
+            if !text.trim().is_empty() {
+                self.insert_task(text, priority, color);
+            }
+        }
+        self.save_tasks();
+    }
+
+    // One task per line, with the add row's priority and color, as a single undo step
+    fn add_lines(&mut self, lines: Vec<String>) {
+        self.snapshot();
+        for line in lines {
+            let text = self.settings.text_cleanup.apply(&line);
+            self.insert_task(text, self.new_task_priority, self.new_task_color());
+        }
+        self.reset_new_task_priority();
+    }
+
+    // The add row's color, or the mapped color for its priority if none was picked
+    fn new_task_color(&self) -> Color32 {
+        match self.settings.priority_color(self.new_task_priority) {
+            Some(color) if !self.new_task_color_chosen => color32_from_array(color),
+            _ => self.new_task_color,
+        }
+    }
+
+    // Add a task and return where it ended up after sorting
+    fn insert_task(&mut self, text: String, priority: u8, color: Color32) -> usize {
+        let added_at = Instant::now();
+        let task = Task {
+            id: Uuid::new_v4(),
+            text,
+            priority: priority as f32,
+            color: array_from_color32(color),
+            selected: false,
+            status: Status::Active,
+            legacy_done: false,
+            icon: None,
+            flagged: false,
+            link: None,
+            context: None,
+            due: None,
+            due_time: None,
+            remind_at: None,
+            reminded: false,
+            progress: 0,
+            created: Some(Local::now()),
+            modified: Some(Local::now()),
+            editing: false,
+            editing_priority: false,
+            text_before_edit: String::new(),
+            expanded: false,
+            added_at: Some(added_at),
+        };
+
+        // The sort is stable, so this decides the order among equal priorities
+        match self.settings.new_task_position {
+            NewTaskPosition::Top => self.tasks.insert(0, task),
+            NewTaskPosition::Bottom => self.tasks.push(task),
+        }
+
+        if self.adding_in_bulk {
+            self.sort_pending = true;
+        } else {
+            self.sort_tasks();
+        }
+        let index = self
+            .tasks
+            .iter()
+            .position(|t| t.added_at == Some(added_at))
+            .unwrap_or_default();
+        self.scroll_to_task = Some((index, None));
+        index
+    }
+
+    // Sort tasks by the configured keys, priority descending unless changed,
+    // optionally keeping completed tasks below all incomplete ones
+    fn sort_tasks(&mut self) {
+        let sink_completed = self.settings.sink_completed;
+        let keys = &self.settings.sort_keys;
+        self.tasks.sort_by(|a, b| {
+            (sink_completed && a.is_done())
+                .cmp(&(sink_completed && b.is_done()))
+                .then_with(|| sort::compare(keys, a, b))
+        });
+    }
+
+    // Priority alone, whatever sort keys are configured; ties keep their order
+    fn sort_by_priority(&mut self) {
+        self.tasks
+            .sort_by(|a, b| sort::SortKey::Priority.compare(a, b));
+    }
+
+    // Rewrite priorities to follow the current order, evenly spaced from 10 down to 1.
+    // Kept fractional so every task stays distinct however long the list; only the
+    // shown number is rounded.
+    fn normalize_priorities(&mut self) {
+        let len = self.tasks.len();
+        if len < 2 {
+            return;
+        }
+        for (i, task) in self.tasks.iter_mut().enumerate() {
+            let step = 9.0 * i as f32 / (len - 1) as f32;
+            task.priority = 10.0 - step;
+            task.touch();
+        }
+    }
+
+    // Merge a file into a copy of the list, to show what merging would change
+    fn preview_merge(&self, path: &str) -> Result<(diff::Diff, Vec<Task>), String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut incoming: Vec<Task> = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+        migrate_tasks(&mut incoming);
+
+        let mut merged = self.tasks.clone();
+        merge::merge(&mut merged, incoming.clone());
+        Ok((diff::diff(&merged, &self.tasks), incoming))
+    }
+
+    fn merge_tasks(&mut self, incoming: Vec<Task>) -> merge::MergeSummary {
+        self.snapshot();
+        let summary = merge::merge(&mut self.tasks, incoming);
+        self.sort_tasks();
+        summary
+    }
+
+    fn compare_with_file(&self, path: &str) -> Result<(diff::Diff, Vec<Task>), String> {
+        let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut other: Vec<Task> = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+        migrate_tasks(&mut other);
+        Ok((diff::diff(&self.tasks, &other), other))
+    }
+
+    // Start or stop the HTTP server to match the settings. A server already on the
+    // right port is left alone, since its old socket may not be released yet.
+    fn apply_api_settings(&mut self, ctx: &Context) {
+        let running = self.api.as_ref().map(api::ApiServer::port);
+        let wanted = self.settings.api_enabled.then_some(self.settings.api_port);
+        if running == wanted {
+            if wanted.is_none() {
+                self.api_error = None;
+            }
+            return;
+        }
+        self.api = None;
+        self.api_error = None;
+        if let Some(port) = wanted {
+            match api::ApiServer::start(port, ctx.clone()) {
+                Ok(server) => self.api = Some(server),
+                Err(err) => self.api_error = Some(err),
+            }
+        }
+    }
+
+    // Commit the saved tasks to the configured repository off the UI thread,
+    // since pulling and pushing can take a while
+    fn start_git_sync(&mut self) {
+        if !self.settings.git_sync_enabled || self.git_sync_job.is_some() {
+            return;
+        }
+        let Ok(serialized) = serde_json::to_string_pretty(&self.tasks) else {
+            return;
+        };
+        let repo = PathBuf::from(self.settings.git_repo_path.trim());
+        let push = self.settings.git_push;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            sender.send(git_sync::sync(&repo, &serialized, push)).ok();
+        });
+        self.git_sync_job = Some(receiver);
+    }
+
+    fn export_tasks(&self, scope: ExportScope) -> Vec<&Task> {
+        let now = Local::now();
+        self.tasks
+            .iter()
+            .filter(|task| match scope {
+                ExportScope::All => true,
+                ExportScope::Visible => self.filter.shows(task, now),
+                ExportScope::Selected => task.selected,
+            })
+            .collect()
+    }
+
+    fn answer_api_call(&mut self, call: api::ApiCall) -> api::ApiReply {
+        match call {
+            api::ApiCall::List => api::ApiReply::ok(api::task_list(&self.tasks)),
+            api::ApiCall::Add { text, priority } => {
+                let text = self.settings.text_cleanup.apply(&text);
+                if text.trim().is_empty() {
+                    return api::ApiReply::error(400, "text is empty");
+                }
+                let priority = priority.unwrap_or(self.settings.default_priority);
+                let color = self
+                    .settings
+                    .priority_color(priority)
+                    .map_or(DEFAULT_TASK_COLOR, color32_from_array);
+                self.snapshot();
+                self.insert_task(text, priority, color);
+                self.save_tasks();
+                api::ApiReply::ok(api::task_list(&self.tasks))
+            }
+            api::ApiCall::Done(id) => {
+                let Some(i) = self.tasks.iter().position(|t| t.id == id) else {
+                    return api::ApiReply::error(404, "no such task");
+                };
+                self.snapshot();
+                let task = &mut self.tasks[i];
+                if !task.is_done() {
+                    task.status = Status::Done;
+                    task.touch();
+                    history::log_completion(task).ok();
+                }
+                if self.settings.sink_completed {
+                    self.sort_tasks();
+                }
+                self.save_tasks();
+                api::ApiReply::ok(api::task_list(&self.tasks))
+            }
+        }
+    }
+
+    // Nudge a task's priority by whole steps of accumulated scroll, so trackpads step
+    // as evenly as mouse wheels. The fraction is kept so a dragged order survives,
+    // and a gesture is one undo step until the held-back re-sort runs.
+    fn scroll_priority(&mut self, i: usize, scroll: f32) {
+        self.priority_scroll += scroll;
+        let steps = (self.priority_scroll / PRIORITY_SCROLL_STEP).trunc();
+        self.priority_scroll -= steps * PRIORITY_SCROLL_STEP;
+        if steps == 0.0 {
+            return;
+        }
+
+        let priority = (self.tasks[i].priority + steps).clamp(1.0, 10.0);
+        if priority != self.tasks[i].priority {
+            let id = self.tasks[i].id;
+            if self.resort_at.is_none() || self.scrolled_task != Some(id) {
+                self.snapshot();
+                self.scrolled_task = Some(id);
+            }
+            self.tasks[i].priority = priority;
+            self.tasks[i].touch();
+            let delay = Duration::from_millis(self.settings.resort_delay_ms);
+            self.resort_at = Some(Instant::now() + delay);
+        }
+    }
+
+    fn task_index(&self, id: Uuid) -> Option<usize> {
+        self.tasks.iter().position(|t| t.id == id)
+    }
+
+    // Where the cursor's task is now, if it still exists
+    fn cursor_index(&self) -> Option<usize> {
+        self.cursor.and_then(|id| self.task_index(id))
+    }
+
+    // Remember the current tasks so the next change can be undone with U
+    fn snapshot(&mut self) {
+        self.undo_snapshot = Some(self.tasks.clone());
+        self.undo_taken_at = None;
+    }
+
+    // Task count and last modification; every change to a task touches it
+    fn undo_state(&self) -> (usize, Option<DateTime<Local>>) {
+        (
+            self.tasks.len(),
+            self.tasks.iter().filter_map(|t| t.modified).max(),
+        )
+    }
+
+    // Drop the snapshot once the list has changed without one, since undoing
+    // would silently throw those changes away
+    fn expire_stale_undo(&mut self) {
+        if self.undo_snapshot.is_none() {
+            return;
+        }
+        let state = self.undo_state();
+        match self.undo_taken_at {
+            None => self.undo_taken_at = Some(state),
+            Some(taken_at) if taken_at != state => {
+                self.undo_snapshot = None;
+                self.undo_taken_at = None;
+            }
+            Some(_) => {}
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(tasks) = self.undo_snapshot.take() {
+            self.tasks = tasks;
+            for task in &mut self.tasks {
+                task.editing = false;
+                task.editing_priority = false;
+            }
+        }
+    }
+
+    // Put the last deleted task back where it was and start editing it, unless
+    // it is already back (after an undo, say)
+    fn restore_deleted_for_edit(&mut self) {
+        let Some((above, mut task)) = self.last_deleted.take() else {
+            return;
+        };
+        if self.tasks.iter().any(|t| t.id == task.id) {
+            return;
+        }
+        self.snapshot();
+        for other in &mut self.tasks {
+            other.selected = false;
+        }
+        task.selected = true;
+        task.start_editing();
+        // Below the task it was under, at the end if that one is gone too
+        let at = match above {
+            Some(id) => self.task_index(id).map_or(self.tasks.len(), |i| i + 1),
+            None => 0,
+        };
+        self.cursor = Some(task.id);
+        self.tasks.insert(at, task);
+        self.scroll_to_task = Some((at, Some(egui::Align::Center)));
+    }
+
+    // Delete the selection, asking first once it reaches the configured size
+    fn request_delete(&mut self) {
+        let count = self.tasks.iter().filter(|t| t.selected).count();
+        let threshold = self.settings.confirm_delete_at;
+        if threshold > 0 && count >= threshold {
+            self.confirm_delete = true;
+        } else {
+            self.delete_selected();
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        let count = self.tasks.iter().filter(|t| t.selected).count();
+        if count == 0 {
+            return;
+        }
+        // The snapshot clones whole tasks, so undo brings back every field
+        self.snapshot();
+        if let Some(i) = self.tasks.iter().rposition(|t| t.selected) {
+            let above = self.tasks[..i].iter().rposition(|t| !t.selected);
+            let above = above.map(|j| self.tasks[j].id);
+            self.last_deleted = Some((above, self.tasks[i].clone()));
+        }
+        self.tasks.retain(|t| !t.selected);
+
+        let what = if count == 1 {
+            "Task deleted".to_string()
+        } else {
+            format!("{} tasks deleted", count)
+        };
+        self.toast = Some((format!("{} — press U to undo", what), Instant::now()));
+    }
+
+    fn request_complete_shown(&mut self) {
+        let now = Local::now();
+        let count = self
+            .tasks
+            .iter()
+            .filter(|t| !t.is_done() && self.filter.shows(t, now))
+            .count();
+        if count >= CONFIRM_COMPLETE_AT {
+            self.confirm_complete_shown = true;
+        } else {
+            self.complete_shown();
+        }
+    }
+
+    // Mark every task the filters let through as done, leaving hidden ones alone
+    fn complete_shown(&mut self) {
+        let filter = self.filter.clone();
+        let now = Local::now();
+        let count = self
+            .tasks
+            .iter()
+            .filter(|t| !t.is_done() && filter.shows(t, now))
+            .count();
+        if count == 0 {
+            return;
+        }
+        self.snapshot();
+        for task in self
+            .tasks
+            .iter_mut()
+            .filter(|t| !t.is_done() && filter.shows(t, now))
+        {
+            task.status = Status::Done;
+            task.touch();
+            history::log_completion(task).ok();
+        }
+        if self.settings.sink_completed {
+            self.sort_tasks();
+        }
+        self.toast = Some((
+            format!("{} tasks marked done — press U to undo", count),
+            Instant::now(),
+        ));
+    }
+
+    fn delete_task(&mut self, i: usize) {
+        self.snapshot();
+        let above = i.checked_sub(1).map(|j| self.tasks[j].id);
+        self.last_deleted = Some((above, self.tasks.remove(i)));
+        self.cursor = None;
+        self.toast = Some(("Task deleted — press U to undo".to_string(), Instant::now()));
+    }
+
+    // Put an unselected copy right after each selected task
+    fn duplicate_selected(&mut self) {
+        self.snapshot();
+        let mut i = 0;
+        while i < self.tasks.len() {
+            if self.tasks[i].selected {
+                let mut copy = self.tasks[i].clone();
+                copy.id = Uuid::new_v4();
+                copy.selected = false;
+                self.tasks.insert(i + 1, copy);
+                i += 1;
+            }
+            i += 1;
+        }
+    }
+
+    // One undo step for the whole recolor, skipped when nothing is selected
+    fn recolor_selected(&mut self, color: Color32) {
+        if !self.tasks.iter().any(|t| t.selected) {
+            return;
+        }
+        self.snapshot();
+        for task in self.tasks.iter_mut().filter(|t| t.selected) {
+            task.color = array_from_color32(color);
+            task.touch();
+        }
+    }
+
+    fn set_selected_priority(&mut self, priority: u8) {
+        self.snapshot();
+        for task in self.tasks.iter_mut().filter(|t| t.selected) {
+            task.priority = priority as f32;
+            task.touch();
+        }
+        self.sort_tasks();
+    }
+
+    fn mark_selected_done(&mut self) {
+        self.snapshot();
+        for task in self.tasks.iter_mut().filter(|t| t.selected && !t.is_done()) {
+            task.status = Status::Done;
+            task.touch();
+            history::log_completion(task).ok();
+        }
+        if self.settings.sink_completed {
+            self.sort_tasks();
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        for task in &mut self.tasks {
+            task.selected = false;
+        }
+    }
+
+    // Hold a docked window against its screen edge at full monitor height, without
+    // a title bar. The platform's taskbar may still cover part of it; winit can't
+    // reserve screen space for the window.
+    fn apply_dock(&mut self, frame: &mut eframe::Frame) {
+        let dock = self.settings.dock;
+        if dock != self.applied_dock {
+            frame.set_decorations(dock == Dock::Floating);
+            self.applied_dock = dock;
+        }
+        let info = frame.info().window_info;
+        let Some(monitor) = info.monitor_size else {
+            return;
+        };
+        let width = self.settings.dock_width.min(monitor.x);
+        let x = match dock {
+            Dock::Floating => return,
+            Dock::Left => 0.0,
+            Dock::Right => monitor.x - width,
+        };
+        let pos = egui::pos2(x, 0.0);
+        let size = Vec2::new(width, monitor.y);
+        if info.position.is_none_or(|p| p.distance(pos) > 1.0) {
+            frame.set_window_pos(pos);
+        }
+        if (info.size - size).length() > 1.0 {
+            frame.set_window_size(size);
+        }
+    }
 
-                            // Priority box with editing support
-                            egui::Frame::none()
-                                .fill(Color32::BLACK)
-                                .stroke(egui::Stroke::new(1.0, Color32::from_rgb(255, 165, 0)))
+    // Board view: a column per status; dropping a card on another column moves it there
+    fn show_board(&mut self, ui: &mut egui::Ui) {
+        let columns = self.settings.board_columns.clone();
+        if columns.is_empty() {
+            ui.label("Pick the board's columns in settings.");
+            return;
+        }
+        let now = Local::now();
+        let mut clicked = None;
+        let mut column_ranges = Vec::with_capacity(columns.len());
+        egui::ScrollArea::vertical()
+            .drag_to_scroll(false)
+            .show(ui, |ui| {
+                ui.columns(columns.len(), |column_uis| {
+                    for (ui, status) in column_uis.iter_mut().zip(&columns) {
+                        column_ranges.push((*status, ui.max_rect().x_range()));
+                        ui.heading(status.title());
+                        let cards = self
+                            .tasks
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, t)| t.status == *status && self.filter.shows(t, now));
+                        for (i, task) in cards {
+                            let (fill, stroke) = row_style(
+                                task,
+                                self.cursor == Some(task.id),
+                                now,
+                                self.settings.stale_after_days,
+                            );
+                            let card = egui::Frame::none()
+                                .fill(fill)
+                                .stroke(stroke)
                                 .rounding(egui::Rounding::same(6.0))
-                                .inner_margin(egui::Margin {
-                                    left: 2.0,
-                                    right: 2.0,
-                                    top: 4.0,
-                                    bottom: 2.0,
-                                })
+                                .inner_margin(egui::Margin::same(6.0))
                                 .show(ui, |ui| {
-                                    let priority_size = Vec2::new(32.0, 24.0);
-                                    ui.allocate_ui(priority_size, |ui| {
-                                        ui.centered_and_justified(|ui| {
-                                            if task.editing_priority {
-                                                let response = ui.add(
-                                                    egui::DragValue::new(&mut task.priority)
-                                                        .clamp_range(1..=10)
-                                                        .speed(1),
-                                                );
-                                                if response.lost_focus()
-                                                    || ui.input(|i| i.key_pressed(Key::Enter))
-                                                {
-                                                    task.editing_priority = false;
-                                                    priority_changed = true;
-                                                }
-                                            } else {
-                                                let response = ui.add(
-                                                    egui::Label::new(
-                                                        egui::RichText::new(
-                                                            task.priority.to_string(),
-                                                        )
-                                                        .color(Color32::WHITE)
-                                                        .size(14.0),
-                                                    )
-                                                    .sense(egui::Sense::click()),
-                                                );
-                                                if response.double_clicked() {
-                                                    task.editing_priority = true;
-                                                }
-                                            }
-                                        });
+                                    ui.set_width(ui.available_width());
+                                    ui.horizontal_wrapped(|ui| {
+                                        if self.settings.priority_display != PriorityDisplay::Hidden
+                                        {
+                                            ui.label(
+                                                egui::RichText::new(
+                                                    task.shown_priority().to_string(),
+                                                )
+                                                .strong()
+                                                .color(Color32::BLACK),
+                                            );
+                                        }
+                                        ui.label(
+                                            egui::RichText::new(&task.text)
+                                                .font(self.settings.task_font_id())
+                                                .color(Color32::BLACK),
+                                        );
                                     });
                                 });
+                            let response = ui.interact(
+                                card.response.rect,
+                                egui::Id::new(("board_card", task.id)),
+                                egui::Sense::click_and_drag(),
+                            );
+                            if response.drag_started() {
+                                self.board_drag = Some(task.id);
+                            }
+                            if response.clicked() {
+                                clicked = Some(i);
+                            }
+                            ui.add_space(4.0);
+                        }
+                    }
+                });
+            });
+
+        if let Some(i) = clicked {
+            self.tasks[i].selected = !self.tasks[i].selected;
+            self.cursor = Some(self.tasks[i].id);
+        }
+
+        let Some(dragged) = self.board_drag.and_then(|id| self.task_index(id)) else {
+            self.board_drag = None;
+            return;
+        };
+        let Some(pos) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        let target = column_ranges
+            .iter()
+            .find(|(_, range)| range.contains(&pos.x))
+            .map(|&(status, _)| status);
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+        ui.ctx()
+            .layer_painter(egui::LayerId::new(
+                egui::Order::Tooltip,
+                egui::Id::new("board_ghost"),
+            ))
+            .text(
+                pos + egui::vec2(12.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                &self.tasks[dragged].text,
+                self.settings.task_font_id(),
+                Color32::from_black_alpha(180),
+            );
+
+        if ui.input(|i| i.pointer.any_released()) {
+            self.board_drag = None;
+            if let Some(status) = target.filter(|&s| s != self.tasks[dragged].status) {
+                self.snapshot();
+                let task = &mut self.tasks[dragged];
+                task.status = status;
+                task.touch();
+                if status == Status::Done {
+                    history::log_completion(task).ok();
+                }
+                if self.settings.sink_completed {
+                    self.sort_tasks();
+                }
+            }
+        }
+    }
+
+    // Move a task to a new position and give it a priority between its new neighbours,
+    // or half a step past the neighbour at either end
+    fn move_task(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.tasks.len() || to >= self.tasks.len() {
+            return;
+        }
+        self.snapshot();
+
+        let task = self.tasks.remove(from);
+        self.tasks.insert(to, task);
+
+        let len = self.tasks.len();
+
+        // Past either end, halfway between the neighbour and the bound
+        let new_priority = if to == 0 {
+            (self.tasks[1].priority + 10.0) / 2.0
+        } else if to == len - 1 {
+            (self.tasks[len - 2].priority + 1.0) / 2.0
+        } else {
+            (self.tasks[to - 1].priority + self.tasks[to + 1].priority) / 2.0
+        };
+
+        self.tasks[to].priority = new_priority;
+        self.tasks[to].touch();
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame) {
+        // Pick up tasks captured from other processes before anything saves over them
+        self.take_inbox();
+        ctx.request_repaint_after(INBOX_CHECK);
+        // Read once, so "today" is the same for every task this frame
+        let local_now = Local::now();
+        let now = Instant::now();
+        if now.duration_since(self.last_save).as_secs() > 30 {
+            if self.save_tasks() {
+                self.start_git_sync();
+            }
+            self.settings.persist();
+            self.last_save = now;
+        }
+
+        self.apply_dock(frame);
+
+        if let Some(server) = &self.api {
+            for (call, reply_to) in server.pending() {
+                let reply = self.answer_api_call(call);
+                reply_to.send(reply).ok();
+            }
+        }
+
+        if let Some(job) = &self.git_sync_job {
+            match job.try_recv() {
+                Ok(result) => {
+                    self.git_sync_error = result.err();
+                    self.git_sync_job = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => ctx.request_repaint_after(Duration::from_secs(1)),
+                Err(mpsc::TryRecvError::Disconnected) => self.git_sync_job = None,
+            }
+        }
+
+        if let Some(at) = self.resort_at {
+            let now = Instant::now();
+            if now >= at {
+                self.resort_at = None;
+                self.sort_tasks();
+            } else {
+                ctx.request_repaint_after(at - now);
+            }
+        }
+
+        // Keep ticking while a reminder is scheduled, even with no input
+        if self.settings.review_enabled {
+            ctx.request_repaint_after(Duration::from_secs(30));
+            let now = Local::now();
+            let quiet = self.settings.is_quiet(now);
+            let due = review::latest_occurrence(
+                self.settings.review_frequency,
+                self.settings.review_hour,
+                self.settings.review_minute,
+                now,
+            );
+            let mut fire = false;
+            match (self.settings.review_last_fired, due) {
+                // Nothing fired yet, so start counting from now instead of firing for the past
+                (None, _) => self.settings.review_last_fired = Some(now),
+                (Some(last), Some(due)) if last < due => {
+                    self.settings.review_last_fired = Some(now);
+                    if !quiet {
+                        fire = true;
+                    } else if self.settings.queue_while_quiet {
+                        self.review_queued = true;
+                    }
+                }
+                _ => {}
+            }
+            if self.review_queued && !quiet {
+                self.review_queued = false;
+                fire = true;
+            }
+            if fire {
+                self.review_prompt = true;
+                frame.set_visible(true);
+                frame.set_minimized(false);
+                frame.set_always_on_top(true);
+            }
+        }
+
+        // Task reminders share the review reminder's quiet hours and window raising
+        if self
+            .tasks
+            .iter()
+            .any(|t| t.remind_at.is_some() && !t.reminded)
+        {
+            ctx.request_repaint_after(Duration::from_secs(30));
+            let now = Local::now();
+            let quiet = self.settings.is_quiet(now);
+            let mut fired = false;
+            for task in &mut self.tasks {
+                let due = !task.reminded && task.remind_at.is_some_and(|at| at <= now);
+                // While quiet, a queued reminder waits and any other is dropped
+                if !due || (quiet && self.settings.queue_while_quiet) {
+                    continue;
+                }
+                task.reminded = true;
+                if !quiet && !task.is_done() {
+                    self.reminders_shown.push(task.text.clone());
+                    fired = true;
+                }
+            }
+            if fired {
+                frame.set_visible(true);
+                frame.set_minimized(false);
+                frame.set_always_on_top(true);
+            }
+        }
+
+        if !self.reminders_shown.is_empty() {
+            egui::Window::new("Reminder")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    for text in &self.reminders_shown {
+                        ui.label(format!("🔔 {}", text));
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        self.reminders_shown.clear();
+                        frame.set_always_on_top(false);
+                    }
+                });
+        }
+
+        if let Some(path) = &mut self.merge_path {
+            let mut preview = false;
+            let mut merge = false;
+            let mut close = false;
+            egui::Window::new("Merge from file")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label("Tasks from another data file are added to this list.");
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        if ui.text_edit_singleline(path).changed() {
+                            self.merge_preview = None;
+                        }
+                    });
+                    match &self.merge_preview {
+                        Some((diff, _)) if diff.is_empty() => {
+                            ui.label(
+                                "Nothing in the file is new or newer; merging changes nothing.",
+                            );
+                        }
+                        Some((diff, _)) => show_preview(
+                            ui,
+                            diff,
+                            [
+                                "Added from the file",
+                                "Removed by the merge",
+                                "Replaced by the file's newer copy",
+                            ],
+                        ),
+                        None => {}
+                    }
+                    if let Some(result) = &self.merge_result {
+                        ui.label(result);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Preview").clicked() {
+                            preview = true;
+                        }
+                        let changes =
+                            matches!(&self.merge_preview, Some((diff, _)) if !diff.is_empty());
+                        if ui
+                            .add_enabled(changes, egui::Button::new("Merge"))
+                            .on_disabled_hover_text("Preview the merge first")
+                            .clicked()
+                        {
+                            merge = true;
+                        }
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+
+            // Nothing is merged until the preview has been seen and confirmed
+            if preview {
+                let path = path.trim().to_string();
+                match self.preview_merge(&path) {
+                    Ok(preview) => {
+                        self.merge_preview = Some(preview);
+                        self.merge_result = None;
+                    }
+                    Err(err) => {
+                        self.merge_preview = None;
+                        self.merge_result = Some(format!("Cannot merge: {}", err));
+                    }
+                }
+            }
+            if let Some((_, incoming)) = self.merge_preview.take_if(|_| merge) {
+                let summary = self.merge_tasks(incoming);
+                self.merge_result = Some(format!(
+                    "Added {}, updated {}, kept {} unchanged",
+                    summary.added, summary.updated, summary.unchanged
+                ));
+            }
+            if close {
+                self.merge_path = None;
+                self.merge_preview = None;
+            }
+        }
+
+        if let Some(path) = &mut self.save_copy_path {
+            let mut save = false;
+            let mut close = false;
+            egui::Window::new("Save somewhere else")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(
+                        "Keep the tasks in a file somewhere writable, such as your home folder. \
+                         Later saves go there too.",
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        ui.text_edit_singleline(path);
+                    });
+                    ui.horizontal(|ui| {
+                        save = ui.button("Save").clicked();
+                        close = ui.button("Close").clicked();
+                    });
+                });
+            if save {
+                let path = PathBuf::from(path.trim());
+                let written = serde_json::to_string_pretty(&self.tasks)
+                    .map_err(io::Error::from)
+                    .and_then(|serialized| fs::write(&path, serialized));
+                // Switch over only once the new file holds the tasks
+                let message = match written {
+                    Ok(()) => {
+                        close = true;
+                        self.settings.data_path = path.display().to_string();
+                        self.settings.persist();
+                        self.save_error = None;
+                        format!("Saving to {} from now on", path.display())
+                    }
+                    Err(err) => format!("Could not save there: {}", err),
+                };
+                self.toast = Some((message, Instant::now()));
+            }
+            if close {
+                self.save_copy_path = None;
+            }
+        }
+
+        if let Some(path) = &mut self.bundle_path {
+            let mut export = false;
+            let mut check = false;
+            let mut import = false;
+            let mut close = false;
+            egui::Window::new("Bundle")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(
+                        "Tasks, settings and history in one file, for moving to another machine.",
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        if ui.text_edit_singleline(path).changed() {
+                            self.bundle_pending = None;
+                        }
+                    });
+                    ui.checkbox(
+                        &mut self.bundle_include_history,
+                        "Include completion history",
+                    );
+                    if let Some(status) = &self.bundle_status {
+                        ui.label(status);
+                    }
+                    if let Some((_, diff)) = &self.bundle_pending {
+                        show_preview(
+                            ui,
+                            diff,
+                            [
+                                "Only in the bundle",
+                                "Only in this list, lost on import",
+                                "Different in the bundle",
+                            ],
+                        );
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Export").clicked() {
+                            export = true;
+                        }
+                        if ui.button("Import…").clicked() {
+                            check = true;
+                        }
+                        if self.bundle_pending.is_some()
+                            && ui.button("Replace everything").clicked()
+                        {
+                            import = true;
+                        }
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+
+            let path = path.trim().to_string();
+            if export {
+                let bundle =
+                    bundle::Bundle::new(&self.tasks, &self.settings, self.bundle_include_history);
+                self.bundle_pending = None;
+                self.bundle_status = Some(match bundle.write(&path) {
+                    Ok(()) => format!("Exported {} tasks", bundle.tasks.len()),
+                    Err(err) => format!("Export failed: {}", err),
+                });
+            }
+            // Read and validate first, so the user sees what would be replaced
+            if check {
+                match bundle::Bundle::read(&path) {
+                    Ok(bundle) => {
+                        self.bundle_status = Some(bundle.summary(self.tasks.len()));
+                        let diff = diff::diff(&bundle.tasks, &self.tasks);
+                        self.bundle_pending = Some((bundle, diff));
+                    }
+                    Err(err) => {
+                        self.bundle_status = Some(format!("Cannot import: {}", err));
+                        self.bundle_pending = None;
+                    }
+                }
+            }
+            if let Some((bundle, _)) = self.bundle_pending.take_if(|_| import) {
+                self.snapshot();
+                self.tasks = bundle.tasks;
+                migrate_tasks(&mut self.tasks);
+                // Where this machine keeps its data file isn't the bundle's to say
+                let data_path = std::mem::take(&mut self.settings.data_path);
+                self.settings = bundle.settings;
+                self.settings.data_path = data_path;
+                self.font_error = fonts::apply(ctx, &self.settings.font_path).err();
+                self.apply_api_settings(ctx);
+                let history_error = bundle
+                    .history
+                    .map(|history| history::replace_all(&history))
+                    .and_then(Result::err);
+                self.sort_tasks();
+                self.save_tasks();
+                self.settings.persist();
+                self.bundle_status = Some(match history_error {
+                    Some(err) => format!("Imported, but the history could not be written: {}", err),
+                    None => "Imported".to_string(),
+                });
+            }
+            if close {
+                self.bundle_path = None;
+                self.bundle_status = None;
+                self.bundle_pending = None;
+            }
+        }
+
+        if let Some(path) = &mut self.compare_path {
+            let mut compare = false;
+            let mut restore = false;
+            let mut close = false;
+            egui::Window::new("Compare with file")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label("See how this list differs from a backup or another data file.");
+                    ui.horizontal(|ui| {
+                        ui.label("File:");
+                        ui.text_edit_singleline(path);
+                    });
+                    match &self.compare_result {
+                        Some(Ok((diff, _))) if diff.is_empty() => {
+                            ui.label("No differences.");
+                        }
+                        Some(Ok((diff, _))) => show_diff(
+                            ui,
+                            diff,
+                            [
+                                "Only in the current list",
+                                "Only in the file",
+                                "Changed since the file was saved",
+                            ],
+                        ),
+                        Some(Err(err)) => {
+                            ui.label(format!("Could not read the file: {}", err));
+                        }
+                        None => {}
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Compare").clicked() {
+                            compare = true;
+                        }
+                        let have_diff =
+                            matches!(&self.compare_result, Some(Ok((diff, _))) if !diff.is_empty());
+                        if ui
+                            .add_enabled(have_diff, egui::Button::new("Restore this file"))
+                            .on_hover_text("Replace the current tasks with the file's; U undoes")
+                            .clicked()
+                        {
+                            restore = true;
+                        }
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    });
+                });
+
+            if compare {
+                let path = path.trim().to_string();
+                self.compare_result = Some(self.compare_with_file(&path));
+            }
+            if restore {
+                if let Some(Ok((_, tasks))) = self.compare_result.take() {
+                    self.snapshot();
+                    self.tasks = tasks;
+                    self.sort_tasks();
+                    self.cursor = None;
+                }
+                close = true;
+            }
+            if close {
+                self.compare_path = None;
+                self.compare_result = None;
+            }
+        }
+
+        if let Some(lines) = &self.pending_paste {
+            let mut choice = None;
+            egui::Window::new("Paste tasks")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("Create {} tasks?", lines.len()));
+                    for line in lines.iter().take(5) {
+                        ui.weak(format!("• {}", line.trim()));
+                    }
+                    if lines.len() > 5 {
+                        ui.weak(format!("…and {} more", lines.len() - 5));
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Create").clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            match choice {
+                Some(true) => {
+                    let lines = self.pending_paste.take().unwrap_or_default();
+                    self.add_lines(lines);
+                }
+                Some(false) => self.pending_paste = None,
+                None => {}
+            }
+        }
+
+        if self.confirm_complete_shown {
+            let count = self
+                .tasks
+                .iter()
+                .filter(|t| !t.is_done() && self.filter.shows(t, local_now))
+                .count();
+            egui::Window::new("Mark tasks done")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("Mark {} shown tasks done?", count));
+                    ui.horizontal(|ui| {
+                        if ui.button("Mark done").clicked() {
+                            self.complete_shown();
+                            self.confirm_complete_shown = false;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirm_complete_shown = false;
+                        }
+                    });
+                });
+        }
+
+        if self.confirm_delete {
+            let count = self.tasks.iter().filter(|t| t.selected).count();
+            egui::Window::new("Delete tasks")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("Delete {} selected tasks?", count));
+                    ui.horizontal(|ui| {
+                        if ui.button("Delete").clicked() {
+                            self.delete_selected();
+                            self.confirm_delete = false;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirm_delete = false;
+                        }
+                    });
+                });
+        }
+
+        if let Some((message, shown_at)) = &self.toast {
+            let left = TOAST_DURATION.saturating_sub(shown_at.elapsed());
+            if left.is_zero() {
+                self.toast = None;
+            } else {
+                egui::Area::new("toast")
+                    .anchor(egui::Align2::CENTER_BOTTOM, Vec2::new(0.0, -16.0))
+                    .interactable(false)
+                    .show(ctx, |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.label(message);
+                        });
+                    });
+                ctx.request_repaint_after(left);
+            }
+        }
+
+        if self.review_prompt {
+            egui::Window::new("Review")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(&self.settings.review_message);
+                    if ui.button("Done").clicked() {
+                        self.review_prompt = false;
+                        frame.set_always_on_top(false);
+                    }
+                });
+        }
+
+        // Each file dropped onto the window becomes a task named after it, linked to its path
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        if !dropped.is_empty() {
+            self.snapshot();
+            for file in dropped {
+                let Some(path) = file.path else { continue };
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                let i = self.insert_task(name, self.new_task_priority, self.new_task_color());
+                self.tasks[i].link = Some(path.display().to_string());
+            }
+        }
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_overlay"),
+            ));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop to add as tasks",
+                egui::FontId::proportional(24.0),
+                Color32::WHITE,
+            );
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::K)) {
+            self.quick_open = Some(String::new());
+            self.quick_open_choice = 0;
+        }
+
+        // Jump to a task by typing part of its text
+        if let Some(query) = &mut self.quick_open {
+            let mut matches: Vec<(i32, usize)> = self
+                .tasks
+                .iter()
+                .enumerate()
+                .filter_map(|(i, task)| fuzzy::score(query, &task.text).map(|score| (score, i)))
+                .collect();
+            matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            matches.truncate(8);
+
+            let (down, up, enter, escape) = ctx.input_mut(|i| {
+                (
+                    i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                    i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                    i.key_pressed(Key::Enter),
+                    i.key_pressed(Key::Escape),
+                )
+            });
+            if down {
+                self.quick_open_choice += 1;
+            }
+            if up {
+                self.quick_open_choice = self.quick_open_choice.saturating_sub(1);
+            }
+            self.quick_open_choice = self.quick_open_choice.min(matches.len().saturating_sub(1));
+
+            let mut chosen = None;
+            egui::Window::new("Jump to task")
+                .title_bar(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_TOP, Vec2::new(0.0, 40.0))
+                .show(ctx, |ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(query)
+                            .hint_text("Jump to task…")
+                            .desired_width(300.0),
+                    );
+                    response.request_focus();
+
+                    for (rank, &(_, i)) in matches.iter().enumerate() {
+                        let label = ui
+                            .selectable_label(rank == self.quick_open_choice, &self.tasks[i].text);
+                        if label.clicked() {
+                            chosen = Some(i);
+                        }
+                    }
+                    if matches.is_empty() && !query.is_empty() {
+                        ui.weak("No matching tasks");
+                    }
+                });
+
+            if enter {
+                chosen = chosen.or(matches.get(self.quick_open_choice).map(|&(_, i)| i));
+            }
+            if let Some(i) = chosen {
+                for (j, task) in self.tasks.iter_mut().enumerate() {
+                    task.selected = j == i;
+                }
+                self.cursor = Some(self.tasks[i].id);
+                if !self.filter.shows(&self.tasks[i], local_now) {
+                    self.filter = Filter::default();
+                }
+                self.scroll_to_task = Some((i, Some(egui::Align::Center)));
+            }
+            if chosen.is_some() || escape {
+                self.quick_open = None;
+            }
+        }
+
+        if let Some(err) = &self.close_save_error {
+            let mut choice = None;
+            egui::Window::new("Couldn't save tasks")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!("Saving failed: {}", err));
+                    ui.label("Closing now will lose changes since the last save.");
+                    ui.horizontal(|ui| {
+                        if ui.button("Retry").clicked() {
+                            choice = Some(false);
+                        }
+                        if ui.button("Quit anyway").clicked() {
+                            choice = Some(true);
+                        }
+                    });
+                });
+
+            // Closing again goes back through on_close_event, which retries the save
+            if let Some(quit_anyway) = choice {
+                self.close_save_error = None;
+                self.quit_without_saving = quit_anyway;
+                frame.close();
+            }
+        }
+
+        if let Some(recovered) = &self.recovered_tasks {
+            let mut choice = None;
+            egui::Window::new("Recover unsaved changes?")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "Found {} tasks saved after the last normal save, probably from a crash.",
+                        recovered.len()
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Recover").clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button("Discard").clicked() {
+                            choice = Some(false);
+                        }
+                    });
+                });
+
+            match choice {
+                Some(true) => {
+                    self.snapshot();
+                    self.tasks = self.recovered_tasks.take().unwrap_or_default();
+                    self.save_tasks();
+                }
+                Some(false) => {
+                    self.recovered_tasks = None;
+                    if let Some(recovery) = get_recovery_path() {
+                        fs::remove_file(recovery).ok();
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if !self.load_issues.is_empty() {
+            let mut dismissed = false;
+            egui::Window::new("Problems loading tasks")
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for issue in &self.load_issues {
+                                ui.label(issue.to_string());
+                            }
+                        });
+                    if ui.button("OK").clicked() {
+                        dismissed = true;
+                    }
+                });
+            if dismissed {
+                self.load_issues.clear();
+            }
+        }
+
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    let data_path = get_data_path(&self.settings);
+                    let folder = data_path.as_ref().and_then(|path| path.parent());
+                    if ui
+                        .add_enabled(folder.is_some(), egui::Button::new("Open data folder"))
+                        .clicked()
+                    {
+                        if let Some(folder) = folder {
+                            open::that(folder).ok();
+                        }
+                        ui.close_menu();
+                    }
+                    let file = data_path.as_ref().filter(|path| path.exists());
+                    if ui
+                        .add_enabled(file.is_some(), egui::Button::new("Open data file"))
+                        .clicked()
+                    {
+                        if let Some(file) = file {
+                            open::that(file).ok();
+                        }
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    ui.menu_button("Copy as Markdown", |ui| {
+                        for scope in ExportScope::ALL {
+                            if ui.button(scope.label()).clicked() {
+                                let text = export::markdown(&self.export_tasks(scope));
+                                ui.output_mut(|o| o.copied_text = text);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    ui.menu_button("Copy as CSV", |ui| {
+                        for scope in ExportScope::ALL {
+                            if ui.button(scope.label()).clicked() {
+                                let text = export::csv(&self.export_tasks(scope));
+                                ui.output_mut(|o| o.copied_text = text);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    // Only tasks with a due date have anything for a calendar to show
+                    ui.menu_button("Copy as iCalendar", |ui| {
+                        for scope in ExportScope::ALL {
+                            if ui.button(scope.label()).clicked() {
+                                let text = export::icalendar(&self.export_tasks(scope));
+                                ui.output_mut(|o| o.copied_text = text);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if ui.button("Merge from file…").clicked() {
+                        self.merge_path = Some(String::new());
+                        self.merge_result = None;
+                        self.merge_preview = None;
+                        ui.close_menu();
+                    }
+                    if ui.button("Export or import bundle…").clicked() {
+                        self.bundle_path = Some(String::new());
+                        self.bundle_status = None;
+                        ui.close_menu();
+                    }
+                    if ui.button("Compare with file…").clicked() {
+                        self.compare_path = Some(String::new());
+                        self.compare_result = None;
+                        ui.close_menu();
+                    }
+                });
+
+                ui.menu_button("Tasks", |ui| {
+                    // One-off sort that keeps any manual order up to now undoable
+                    if ui.button("Sort by priority now").clicked() {
+                        self.snapshot();
+                        self.sort_by_priority();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button("Normalize priorities")
+                        .on_hover_text(
+                            "Spread priorities evenly from 10 down to 1 in the current order",
+                        )
+                        .clicked()
+                    {
+                        self.snapshot();
+                        self.normalize_priorities();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .button("Mark shown tasks done")
+                        .on_hover_text("Shift+D; tasks hidden by filters are left alone")
+                        .clicked()
+                    {
+                        self.request_complete_shown();
+                        ui.close_menu();
+                    }
+                });
+
+                if ui.button("⚙ Settings").clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+
+                ui.toggle_value(&mut self.filter.flagged_only, "★ Flagged only");
+                ui.toggle_value(&mut self.filter.waiting_only, "⏳ Waiting only");
+                // One button per context in use; clicking the active one shows all again
+                let mut contexts = contexts_in_use(&self.tasks);
+                if let Some(context) = &self.filter.context {
+                    if !contexts.contains(context) {
+                        contexts.push(context.clone());
+                    }
+                }
+                for context in contexts {
+                    let active = self.filter.context.as_ref() == Some(&context);
+                    if ui
+                        .selectable_label(active, &context)
+                        .on_hover_text("Show only tasks in this context")
+                        .clicked()
+                    {
+                        self.filter.context = (!active).then_some(context);
+                    }
+                }
+                let stale_days = self.settings.stale_after_days;
+                if stale_days > 0 {
+                    let mut stale_only = self.filter.stale_only.is_some();
+                    ui.toggle_value(&mut stale_only, "Stale only")
+                        .on_hover_text(format!(
+                            "Unfinished tasks at least {} days old",
+                            stale_days
+                        ));
+                    self.filter.stale_only = stale_only.then_some(stale_days);
+                } else {
+                    self.filter.stale_only = None;
+                }
+                ui.toggle_value(&mut self.settings.board_view, "Board")
+                    .on_hover_text("Show a column per status instead of the list");
+
+                let presets = self.settings.palette.palette().presets;
+                let color_filter = self.filter.color;
+                let color_label = match presets
+                    .iter()
+                    .find(|(color, _)| Some(array_from_color32(*color)) == color_filter)
+                {
+                    Some((_, name)) => format!("🎨 {}", name),
+                    None if color_filter.is_some() => "🎨 Custom color".to_string(),
+                    None => "🎨 Any color".to_string(),
+                };
+                ui.menu_button(color_label, |ui| {
+                    if ui.button("Any color").clicked() {
+                        self.filter.color = None;
+                        ui.close_menu();
+                    }
+                    for &(color, name) in presets {
+                        if ui.add(egui::Button::new(name).fill(color)).clicked() {
+                            self.filter.color = Some(array_from_color32(color));
+                            ui.close_menu();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Show only tasks of one color; Alt+click a swatch to pick");
+
+                ui.toggle_value(&mut self.settings.do_not_disturb, "🔕")
+                    .on_hover_text("Do not disturb: hold back reminders");
+                if !self.settings.do_not_disturb && self.settings.is_quiet(Local::now()) {
+                    ui.weak("Quiet hours");
+                }
+                if let Some((_, streak)) = self.streak.filter(|&(_, days)| days > 0) {
+                    ui.label(format!("🔥 {}", streak))
+                        .on_hover_text(match streak {
+                            1 => "A task done today or yesterday".to_string(),
+                            _ => format!("{} days in a row with a task done", streak),
+                        });
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let collapsed = &mut self.settings.controls_collapsed;
+                    let (chevron, hint) = if *collapsed {
+                        ("⏷", "Show controls")
+                    } else {
+                        ("⏶", "Hide controls")
+                    };
+                    if ui.button(chevron).on_hover_text(hint).clicked() {
+                        *collapsed = !*collapsed;
+                    }
+                });
+            });
+        });
+
+        let mut show_settings = self.show_settings;
+        egui::Window::new("Settings")
+            .open(&mut show_settings)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.settings.header_shown, "Header:");
+                    ui.add_enabled(
+                        self.settings.header_shown,
+                        egui::TextEdit::singleline(&mut self.settings.header_icon)
+                            .desired_width(30.0),
+                    );
+                    ui.add_enabled(
+                        self.settings.header_shown,
+                        egui::TextEdit::singleline(&mut self.settings.header_text)
+                            .desired_width(120.0),
+                    );
+                    ui.add_enabled_ui(self.settings.header_shown, |ui| {
+                        ui.color_edit_button_srgba_unmultiplied(&mut self.settings.header_color);
+                    });
+                });
+
+                ui.checkbox(
+                    &mut self.settings.streak_enabled,
+                    "Show my completion streak",
+                )
+                .on_hover_text("Days in a row with a task done, from the completion history");
+
+                ui.horizontal(|ui| {
+                    ui.label("Palette:");
+                    egui::ComboBox::from_id_source("palette")
+                        .selected_text(self.settings.palette.label())
+                        .show_ui(ui, |ui| {
+                            for kind in PaletteKind::ALL {
+                                ui.selectable_value(&mut self.settings.palette, kind, kind.label());
+                            }
+                        });
+                });
+
+                ui.checkbox(
+                    &mut self.settings.priority_colors_enabled,
+                    "Color new tasks by priority",
+                )
+                .on_hover_text("Used until a color is picked for the new task; 🔄 goes back to it");
+                if self.settings.priority_colors_enabled {
+                    ui.horizontal_wrapped(|ui| {
+                        for (priority, color) in
+                            self.settings.priority_colors.iter_mut().enumerate()
+                        {
+                            ui.label((priority + 1).to_string());
+                            ui.color_edit_button_srgba_unmultiplied(color);
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Add new tasks at:");
+                    ui.radio_value(
+                        &mut self.settings.new_task_position,
+                        NewTaskPosition::Top,
+                        "Top",
+                    );
+                    ui.radio_value(
+                        &mut self.settings.new_task_position,
+                        NewTaskPosition::Bottom,
+                        "Bottom",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("New task priority:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.default_priority)
+                                .clamp_range(1..=10)
+                                .speed(1),
+                        )
+                        .changed()
+                    {
+                        self.new_task_priority = self.settings.default_priority;
+                    }
+                    ui.checkbox(
+                        &mut self.settings.remember_last_priority,
+                        "Keep the last one used",
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Show priority as:");
+                    ui.radio_value(
+                        &mut self.settings.priority_display,
+                        PriorityDisplay::Number,
+                        "Number",
+                    );
+                    ui.radio_value(
+                        &mut self.settings.priority_display,
+                        PriorityDisplay::Bar,
+                        "Bar",
+                    );
+                    ui.radio_value(
+                        &mut self.settings.priority_display,
+                        PriorityDisplay::Hidden,
+                        "Hidden",
+                    )
+                    .on_hover_text("Rank tasks by dragging them instead");
+                    if self.settings.priority_display == PriorityDisplay::Bar {
+                        ui.checkbox(&mut self.settings.show_bar_number, "with number");
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Priority side:");
+                    ui.radio_value(&mut self.settings.priority_side, Side::Left, "Left");
+                    ui.radio_value(&mut self.settings.priority_side, Side::Right, "Right");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Done checkbox side:");
+                    ui.radio_value(&mut self.settings.checkbox_side, Side::Left, "Left");
+                    ui.radio_value(&mut self.settings.checkbox_side, Side::Right, "Right");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("When adding or editing:");
+                    ui.checkbox(
+                        &mut self.settings.text_cleanup.collapse_whitespace,
+                        "collapse spaces",
+                    );
+                    ui.checkbox(
+                        &mut self.settings.text_cleanup.capitalize_first,
+                        "capitalize the first letter",
+                    );
+                });
+
+                ui.checkbox(&mut self.settings.show_row_numbers, "Number the rows");
+
+                ui.horizontal(|ui| {
+                    ui.label("Mark tasks stale after:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.stale_after_days)
+                            .clamp_range(0..=365)
+                            .suffix(" days"),
+                    )
+                    .on_hover_text("0 turns this off");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Max lines per task:");
+                    ui.add(egui::DragValue::new(&mut self.settings.max_lines).clamp_range(0..=20))
+                        .on_hover_text("0 shows every line");
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Window:");
+                    ui.radio_value(&mut self.settings.dock, Dock::Floating, "Floating");
+                    ui.radio_value(&mut self.settings.dock, Dock::Left, "Docked left");
+                    ui.radio_value(&mut self.settings.dock, Dock::Right, "Docked right");
+                    if self.settings.dock != Dock::Floating {
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.dock_width)
+                                .clamp_range(200.0..=1200.0)
+                                .speed(5.0)
+                                .suffix(" px"),
+                        );
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Columns on wide windows:");
+                    ui.add(egui::DragValue::new(&mut self.settings.max_columns).clamp_range(1..=6))
+                        .on_hover_text("1 keeps a single column");
+                    ui.label("each at least");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.min_column_width)
+                            .clamp_range(200.0..=800.0)
+                            .speed(5.0)
+                            .suffix(" px"),
+                    );
+                });
+
+                let mut resort = false;
+                ui.horizontal(|ui| {
+                    ui.label("Sort by:");
+                    ui.menu_button("Presets", |ui| {
+                        for (name, keys) in sort::PRESETS {
+                            if ui.button(name).clicked() {
+                                self.settings.sort_keys = keys.to_vec();
+                                resort = true;
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+                // Three slots; clearing one drops it and every slot after it
+                for slot in 0..3 {
+                    if slot > self.settings.sort_keys.len() {
+                        break;
+                    }
+                    let current = self.settings.sort_keys.get(slot).copied();
+                    let mut chosen = current;
+                    ui.horizontal(|ui| {
+                        ui.label(if slot == 0 { "First by" } else { "Then by" });
+                        egui::ComboBox::from_id_source(("sort_key", slot))
+                            .selected_text(current.map_or("—", sort::SortKey::label))
+                            .show_ui(ui, |ui| {
+                                if slot > 0 {
+                                    ui.selectable_value(&mut chosen, None, "—");
+                                }
+                                for key in sort::SortKey::ALL {
+                                    ui.selectable_value(&mut chosen, Some(key), key.label());
+                                }
+                            });
+                    });
+                    if chosen != current {
+                        match chosen {
+                            Some(key) if slot < self.settings.sort_keys.len() => {
+                                self.settings.sort_keys[slot] = key;
+                            }
+                            Some(key) => self.settings.sort_keys.push(key),
+                            None => self.settings.sort_keys.truncate(slot),
+                        }
+                        resort = true;
+                    }
+                }
+
+                ui.checkbox(
+                    &mut self.settings.defer_sort_while_adding,
+                    "Sort new tasks once I stop adding",
+                )
+                .on_hover_text(
+                    "Tasks added with Enter stay in typing order until the task box is left",
+                );
+                if ui
+                    .checkbox(
+                        &mut self.settings.sink_completed,
+                        "Keep completed tasks at the bottom",
+                    )
+                    .changed()
+                {
+                    resort = true;
+                }
+                if resort {
+                    self.sort_tasks();
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Board columns:");
+                    for status in Status::ALL {
+                        let columns = &mut self.settings.board_columns;
+                        let mut shown = columns.contains(&status);
+                        // A column switched back on goes to the right end
+                        if ui.checkbox(&mut shown, status.title()).changed() {
+                            if shown {
+                                columns.push(status);
+                            } else {
+                                columns.retain(|&s| s != status);
+                            }
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Animation frame rate:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.max_fps)
+                            .clamp_range(1..=120)
+                            .suffix(" fps"),
+                    );
+                })
+                .response
+                .on_hover_text("Lower rates use less CPU while something is animating");
+                ui.horizontal(|ui| {
+                    ui.label("Re-sort after scrolling a priority:");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.resort_delay_ms)
+                            .clamp_range(0..=5000)
+                            .speed(10)
+                            .suffix(" ms"),
+                    );
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Font file:");
+                    ui.text_edit_singleline(&mut self.settings.font_path)
+                        .on_hover_text("TTF or OTF file, leave empty for the default font");
+                    if ui.button("Apply").clicked() {
+                        self.font_error = fonts::apply(ctx, &self.settings.font_path).err();
+                    }
+                });
+                if let Some(error) = &self.font_error {
+                    ui.colored_label(Color32::RED, error);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Task font:");
+                    ui.radio_value(
+                        &mut self.settings.task_font,
+                        TaskFont::Proportional,
+                        "Proportional",
+                    );
+                    ui.radio_value(
+                        &mut self.settings.task_font,
+                        TaskFont::Monospace,
+                        "Monospace",
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.task_font_size)
+                            .clamp_range(10.0..=32.0)
+                            .suffix(" pt"),
+                    );
+                });
+
+                if ui
+                    .checkbox(
+                        &mut self.settings.review_enabled,
+                        "Remind me to review tasks",
+                    )
+                    .changed()
+                {
+                    // Don't fire straight away for occurrences missed while it was off
+                    self.settings.review_last_fired = Some(Local::now());
+                }
+                if self.settings.review_enabled {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source("review_frequency")
+                            .selected_text(self.settings.review_frequency.label())
+                            .show_ui(ui, |ui| {
+                                for frequency in ReviewFrequency::ALL {
+                                    ui.selectable_value(
+                                        &mut self.settings.review_frequency,
+                                        frequency,
+                                        frequency.label(),
+                                    );
+                                }
+                            });
+                        ui.label("at");
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.review_hour)
+                                .clamp_range(0..=23),
+                        );
+                        ui.label(":");
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.review_minute)
+                                .clamp_range(0..=59)
+                                .custom_formatter(|n, _| format!("{:02}", n)),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Message:");
+                        ui.text_edit_singleline(&mut self.settings.review_message);
+                    });
+                }
+
+                ui.checkbox(&mut self.settings.quiet_hours_enabled, "Quiet hours");
+                if self.settings.quiet_hours_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label("From");
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.quiet_start_hour)
+                                .clamp_range(0..=23)
+                                .suffix(":00"),
+                        );
+                        ui.label("to");
+                        ui.add(
+                            egui::DragValue::new(&mut self.settings.quiet_end_hour)
+                                .clamp_range(0..=23)
+                                .suffix(":00"),
+                        );
+                    });
+                }
+                ui.checkbox(
+                    &mut self.settings.queue_while_quiet,
+                    "Show held back reminders once quiet time ends",
+                );
+
+                if !self.settings.data_path.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Data file: {}", self.settings.data_path));
+                        if ui
+                            .button("Use the default")
+                            .on_hover_text("Save the current tasks to the data folder again")
+                            .clicked()
+                        {
+                            self.settings.data_path.clear();
+                            self.save_tasks();
+                        }
+                    });
+                }
+
+                ui.checkbox(
+                    &mut self.settings.git_sync_enabled,
+                    "Commit tasks to a Git repository",
+                );
+                if self.settings.git_sync_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label("Repository:");
+                        ui.text_edit_singleline(&mut self.settings.git_repo_path);
+                    });
+                    ui.checkbox(
+                        &mut self.settings.git_push,
+                        "Pull and push after committing",
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    let toggled = ui
+                        .checkbox(&mut self.settings.api_enabled, "Local HTTP API on port")
+                        .on_hover_text(
+                            "GET /tasks, POST /tasks, POST /tasks/{id}/done on 127.0.0.1",
+                        )
+                        .changed();
+                    ui.add_enabled(
+                        !self.settings.api_enabled,
+                        egui::DragValue::new(&mut self.settings.api_port).clamp_range(1024..=65535),
+                    );
+                    if toggled {
+                        self.apply_api_settings(ctx);
+                    }
+                });
+                if let Some(error) = &self.api_error {
+                    ui.colored_label(Color32::RED, format!("API not started: {}", error));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Confirm deleting");
+                    ui.add(
+                        egui::DragValue::new(&mut self.settings.confirm_delete_at)
+                            .clamp_range(0..=100),
+                    )
+                    .on_hover_text("0 never asks");
+                    ui.label("or more tasks");
+                });
+
+                ui.checkbox(
+                    &mut self.settings.strict_load,
+                    "Strictly validate tasks on load",
+                )
+                .on_hover_text("Takes effect on next start");
+            });
+        self.show_settings = show_settings;
+
+        let palette = self.settings.palette.palette();
+
+        // Strip below the list that deletes a task dropped on it, there only while dragging
+        // so it never covers rows that could be drop targets
+        let trash_rect = self.dragging_task.is_some().then(|| {
+            egui::TopBottomPanel::bottom("drag_trash")
+                .show(ctx, |ui| {
+                    let (rect, _) = ui.allocate_exact_size(
+                        Vec2::new(ui.available_width(), 36.0),
+                        egui::Sense::hover(),
+                    );
+                    let over = ctx
+                        .pointer_interact_pos()
+                        .is_some_and(|pos| rect.contains(pos));
+                    let (fill, text_color) = if over {
+                        (Color32::from_rgb(200, 50, 50), Color32::WHITE)
+                    } else {
+                        (
+                            Color32::from_rgba_unmultiplied(200, 50, 50, 60),
+                            Color32::from_gray(60),
+                        )
+                    };
+                    ui.painter()
+                        .rect_filled(rect, egui::Rounding::same(8.0), fill);
+                    ui.painter().text(
+                        rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        "🗑 Drop here to delete",
+                        egui::FontId::proportional(16.0),
+                        text_color,
+                    );
+                    rect
+                })
+                .inner
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(err) = &self.save_error {
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(
+                        Color32::RED,
+                        format!(
+                            "Tasks could not be saved: {}. \
+                             Changes are only in memory until a save works.",
+                            err
+                        ),
+                    );
+                    if ui.small_button("Save somewhere else…").clicked() {
+                        self.save_copy_path = Some(String::new());
+                    }
+                });
+            }
+            if let Some(err) = &self.git_sync_error {
+                let mut dismiss = false;
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(Color32::RED, format!("Git sync failed: {}", err));
+                    dismiss = ui.small_button("Dismiss").clicked();
+                });
+                if dismiss {
+                    self.git_sync_error = None;
+                }
+            }
+
+            // Header, add row and presets can be collapsed to leave only the list
+            if !self.settings.controls_collapsed {
+                if self.settings.header_shown {
+                    let text = format!(
+                        "{} {}",
+                        self.settings.header_icon.trim(),
+                        self.settings.header_text.trim()
+                    );
+                    let text = text.trim();
+                    let font_id = egui::FontId::proportional(32.0);
 
-                            ui.add_space(10.0);
+                    // Draw shadow
+                    ui.painter().text(
+                        ui.min_rect().center_top() + egui::vec2(2.0, 2.0),
+                        egui::Align2::CENTER_TOP,
+                        text,
+                        font_id.clone(),
+                        Color32::from_rgba_unmultiplied(0, 0, 0, 150),
+                    );
 
-                            let available_width = ui.available_width();
-                            let font_id = egui::FontId::proportional(14.0);
+                    // Draw main colored text
+                    ui.painter().text(
+                        ui.min_rect().center_top(),
+                        egui::Align2::CENTER_TOP,
+                        text,
+                        font_id,
+                        color32_from_array(self.settings.header_color),
+                    );
 
-                            if task.editing {
-                                let response = ui.add_sized(
-                                    Vec2::new(available_width, 30.0),
-                                    egui::TextEdit::singleline(&mut task.text)
-                                        .font(font_id.clone())
-                                        .desired_width(f32::INFINITY),
-                                );
+                    ui.add_space(50.0);
+                }
 
-                                if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter))
-                                {
-                                    task.editing = false;
+                ui.horizontal(|ui| {
+                    let task_label = ui.label("Task:");
+                    let input_id = egui::Id::new("new_task_text");
+
+                    // A paste of several lines makes a task of each line instead
+                    if ctx.memory(|m| m.has_focus(input_id)) {
+                        let mut pasted = Vec::new();
+                        ctx.input_mut(|i| {
+                            i.events.retain(|event| match event {
+                                egui::Event::Paste(text) if text.trim().contains('\n') => {
+                                    pasted.extend(
+                                        text.lines()
+                                            .filter(|line| !line.trim().is_empty())
+                                            .map(str::to_string),
+                                    );
+                                    false
                                 }
-                            } else {
-                                let font_id = egui::FontId::proportional(16.0);
-                                let padding = 12.0;
-                                let text_width = available_width - padding;
-
-                                // Layout job to measure wrapped text height
-                                let job = egui::text::LayoutJob::simple(
-                                    task.text.clone(),
-                                    font_id.clone(),
-                                    Color32::BLACK,
-                                    text_width,
-                                );
-                                let galley = ui.fonts(|f| f.layout_job(job));
-                                let text_height = galley.size().y;
-                                let block_height = text_height + padding;
-
-                                // Allocate a draggable and clickable response for the task text area
-                                let response = ui.allocate_response(
-                                    Vec2::new(available_width, block_height),
-                                    egui::Sense::click_and_drag(),
-                                );
+                                _ => true,
+                            })
+                        });
+                        if pasted.len() >= CONFIRM_PASTE_AT {
+                            self.pending_paste = Some(pasted);
+                        } else if !pasted.is_empty() {
+                            self.add_lines(pasted);
+                        }
+                    }
 
-                                // Draw the wrapped text with padding
-                                ui.painter().galley(
-                                    response.rect.left_top() + egui::vec2(6.0, 6.0),
-                                    galley,
-                                );
+                    let response = ui
+                        .add(egui::TextEdit::singleline(&mut self.new_task_text).id(input_id))
+                        .labelled_by(task_label.id);
+                    if self.focus_new_task {
+                        response.request_focus();
+                        self.focus_new_task = false;
+                    }
 
-                                // Editing toggle on double-click
-                                if response.double_clicked() {
-                                    task.editing = true;
-                                }
+                    // Enter adds the task and keeps the input focused for the next one
+                    let mut add = false;
+                    let entered = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    if entered {
+                        add = true;
+                        response.request_focus();
+                    }
 
-                                // Selection toggle on click
-                                if response.clicked() {
-                                    task.selected = !task.selected;
-                                }
+                    if self.settings.priority_display != PriorityDisplay::Hidden {
+                        let priority_label = ui.label("Priority:");
+                        ui.add(
+                            egui::DragValue::new(&mut self.new_task_priority)
+                                .clamp_range(1..=10)
+                                .speed(1),
+                        )
+                        .labelled_by(priority_label.id);
+                    }
 
-                                // Drag handling: track drag start and drag over target
-                                if response.drag_started() {
-                                    self.dragging_task = Some(i);
-                                }
+                    let mut color_arr = array_from_color32(self.new_task_color());
+                    let response = ui.color_edit_button_srgba_unmultiplied(&mut color_arr);
+                    response.widget_info(|| {
+                        egui::WidgetInfo::labeled(egui::WidgetType::ColorButton, "New task color")
+                    });
+                    if response.changed() {
+                        self.new_task_color = color32_from_array(color_arr);
+                        self.new_task_color_chosen = true;
+                    }
+
+                    if ui.button("➕ Add").clicked() {
+                        add = true;
+                    }
+
+                    if add {
+                        self.add_from_row(entered);
+                    }
+                    if !entered && !ctx.memory(|m| m.has_focus(input_id)) {
+                        self.end_bulk_add();
+                    }
+                });
+
+                ui.add_space(12.0);
+
+                // Color presets
+                ui.horizontal(|ui| {
+                    for &(color, name) in palette.presets {
+                        let response = ui.add(
+                            egui::Button::new("   ")
+                                .fill(color)
+                                .frame(true)
+                                .min_size(Vec2::new(24.0, 24.0)),
+                        );
+                        response.widget_info(|| {
+                            egui::WidgetInfo::labeled(egui::WidgetType::Button, name)
+                        });
+                        let response = response.on_hover_text(name);
+                        if response.clicked() && ui.input(|i| i.modifiers.alt) {
+                            // Alt+click filters to the color instead of applying it
+                            let color = array_from_color32(color);
+                            self.filter.color = (self.filter.color != Some(color)).then_some(color);
+                        } else if response.clicked() {
+                            self.recolor_selected(color);
+                            self.new_task_color = color;
+                            self.new_task_color_chosen = true;
+                        }
+                    }
+
+                    // Reset color back to the default
+                    let response = ui.add(
+                        egui::Button::new("🔄")
+                            .fill(DEFAULT_TASK_COLOR)
+                            .frame(true)
+                            .min_size(Vec2::new(24.0, 24.0)),
+                    );
+                    response.widget_info(|| {
+                        egui::WidgetInfo::labeled(egui::WidgetType::Button, "Reset color")
+                    });
+                    if response.on_hover_text("Reset color").clicked() {
+                        self.recolor_selected(DEFAULT_TASK_COLOR);
+                        self.new_task_color = DEFAULT_TASK_COLOR;
+                        self.new_task_color_chosen = false;
+                    }
+                });
+
+                ui.add_space(16.0);
+            }
+
+            // Keyboard navigation
+            if !ctx.wants_keyboard_input() {
+                // J/K, Home/End and PageUp/PageDown move the cursor among the shown tasks.
+                // While at most one task is selected the selection follows the cursor, as
+                // it always has. Shift adds each task moved onto to the selection and Space
+                // toggles the cursor's task, after which moving leaves the selection alone.
+                let shown: Vec<usize> = (0..self.tasks.len())
+                    .filter(|&i| self.filter.shows(&self.tasks[i], local_now))
+                    .collect();
+                let last = shown.len().saturating_sub(1);
+                let page = self.page_rows.max(1);
+                let selected_count = self.tasks.iter().filter(|t| t.selected).count();
+                let cursor_idx = self
+                    .cursor_index()
+                    .or_else(|| self.tasks.iter().position(|t| t.selected));
+                let current = cursor_idx.and_then(|i| shown.iter().position(|&j| j == i));
+                let (target, extend) = ui.input(|i| {
+                    let target = if i.key_pressed(Key::J) {
+                        Some(current.map_or(0, |c| (c + 1).min(last)))
+                    } else if i.key_pressed(Key::K) {
+                        Some(current.map_or(0, |c| c.saturating_sub(1)))
+                    } else if i.key_pressed(Key::Home) {
+                        Some(0)
+                    } else if i.key_pressed(Key::End) {
+                        Some(last)
+                    } else if i.key_pressed(Key::PageUp) {
+                        Some(current.unwrap_or(0).saturating_sub(page))
+                    } else if i.key_pressed(Key::PageDown) {
+                        Some(current.map_or(0, |c| c + page).min(last))
+                    } else {
+                        None
+                    };
+                    (target, i.modifiers.shift)
+                });
+                if let Some(&target) = target.and_then(|t| shown.get(t)) {
+                    if extend {
+                        self.tasks[target].selected = true;
+                    } else if selected_count <= 1 {
+                        for (i, task) in self.tasks.iter_mut().enumerate() {
+                            task.selected = i == target;
+                        }
+                    }
+                    self.cursor = Some(self.tasks[target].id);
+                    self.scroll_to_task = Some((target, None));
+                }
+
+                if ui.input(|i| i.key_pressed(Key::Space)) {
+                    if let Some(i) = cursor_idx {
+                        self.tasks[i].selected = !self.tasks[i].selected;
+                        self.cursor = Some(self.tasks[i].id);
+                    }
+                }
+
+                if ui.input_mut(|i| i.consume_key(Modifiers::SHIFT, Key::D)) {
+                    self.request_complete_shown();
+                } else if ui.input(|i| i.key_pressed(Key::D)) {
+                    self.request_delete();
+                }
+
+                if ui.input_mut(|i| i.consume_key(Modifiers::SHIFT, Key::U)) {
+                    self.restore_deleted_for_edit();
+                } else if ui.input(|i| i.key_pressed(Key::U)) {
+                    self.undo();
+                }
+
+                // O steps through active tasks with a due date, soonest (so overdue) first
+                if ui.input(|i| i.key_pressed(Key::O)) {
+                    let mut due: Vec<(DateTime<Local>, usize)> = shown
+                        .iter()
+                        .filter(|&&i| self.tasks[i].status == Status::Active)
+                        .filter_map(|&i| Some((self.tasks[i].due_at()?, i)))
+                        .collect();
+                    due.sort();
+                    let cursor = self.cursor_index();
+                    let next = match due.iter().position(|&(_, i)| Some(i) == cursor) {
+                        Some(at) => due.get(at + 1).or(due.first()),
+                        None => due.first(),
+                    };
+                    if let Some(&(_, target)) = next {
+                        for (i, task) in self.tasks.iter_mut().enumerate() {
+                            task.selected = i == target;
+                        }
+                        self.cursor = Some(self.tasks[target].id);
+                        self.scroll_to_task = Some((target, Some(egui::Align::Center)));
+                    }
+                }
+
+                // P then a digit sets every selected task's priority, 0 standing for 10
+                let anything_selected = self.tasks.iter().any(|t| t.selected);
+                if self.priority_shortcut && anything_selected {
+                    const DIGITS: [Key; 10] = [
+                        Key::Num0,
+                        Key::Num1,
+                        Key::Num2,
+                        Key::Num3,
+                        Key::Num4,
+                        Key::Num5,
+                        Key::Num6,
+                        Key::Num7,
+                        Key::Num8,
+                        Key::Num9,
+                    ];
+                    let digit = ui.input(|i| DIGITS.iter().position(|&key| i.key_pressed(key)));
+                    if let Some(digit) = digit {
+                        self.priority_shortcut = false;
+                        self.set_selected_priority(if digit == 0 { 10 } else { digit as u8 });
+                    } else if ui.input(|i| i.key_pressed(Key::Escape)) {
+                        self.priority_shortcut = false;
+                    }
+                } else {
+                    self.priority_shortcut =
+                        anything_selected && ui.input(|i| i.key_pressed(Key::P));
+                }
+            }
 
-                                if response.hovered() && ui.input(|i| i.pointer.any_released()) {
-                                    self.drag_over_task = Some(i);
+            // Actions on the selection, only shown while something is selected
+            let selected_count = self.tasks.iter().filter(|t| t.selected).count();
+            if selected_count > 0 {
+                ui.horizontal_wrapped(|ui| {
+                    if self.priority_shortcut {
+                        ui.strong("Priority: press 1–9, or 0 for 10");
+                    }
+                    ui.label(format!("{} selected:", selected_count));
+                    if ui.button("🗑 Delete").clicked() {
+                        self.request_delete();
+                    }
+                    if ui.button("Duplicate").clicked() {
+                        self.duplicate_selected();
+                    }
+                    ui.menu_button("Recolor", |ui| {
+                        ui.horizontal(|ui| {
+                            for &(color, name) in palette.presets {
+                                let response = ui.add(
+                                    egui::Button::new("   ")
+                                        .fill(color)
+                                        .min_size(Vec2::new(24.0, 24.0)),
+                                );
+                                response.widget_info(|| {
+                                    egui::WidgetInfo::labeled(egui::WidgetType::Button, name)
+                                });
+                                if response.on_hover_text(name).clicked() {
+                                    self.recolor_selected(color);
+                                    ui.close_menu();
                                 }
                             }
                         });
                     });
-
+                    ui.menu_button("Set priority", |ui| {
+                        ui.horizontal(|ui| {
+                            for priority in 1..=10 {
+                                if ui.button(priority.to_string()).clicked() {
+                                    self.set_selected_priority(priority);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                    if ui.button("✔ Mark done").clicked() {
+                        self.mark_selected_done();
+                    }
+                    if ui.button("Clear selection").clicked() {
+                        self.clear_selection();
+                    }
+                });
                 ui.add_space(4.0);
             }
 
-            if priority_changed {
-                self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+            if self.settings.board_view {
+                self.show_board(ui);
+                return;
             }
 
-            // After the loop, handle reordering and priority adjustment if drag completed
-            if let (Some(from), Some(to)) = (self.dragging_task, self.drag_over_task) {
-                if from != to && from < self.tasks.len() && to < self.tasks.len() {
-                    let task = self.tasks.remove(from);
-                    self.tasks.insert(to, task);
+            // Show tasks
+            let mut priority_changed = false;
+            let mut priority_edit_started = None;
+            let mut move_request = None;
+            let mut template_request = None;
+            let mut priority_scroll = None;
+            let mut done_toggled = None;
+            let sink_completed = self.settings.sink_completed;
+            // Contexts offered in each task's picker: those in use plus the usual ones
+            let mut known_contexts = contexts_in_use(&self.tasks);
+            for suggestion in CONTEXT_SUGGESTIONS {
+                if !known_contexts.iter().any(|c| c == suggestion) {
+                    known_contexts.push(suggestion.to_string());
+                }
+            }
+            let mut completed_collapsed = self.settings.completed_collapsed;
+            let task_count = self.tasks.len();
+            // Rect of each shown row along with its task index
+            let mut row_rects = Vec::with_capacity(task_count);
+            let scroll_to_task = self.scroll_to_task.take();
+            let cursor = self.cursor;
+            let animation_frame = self.settings.animation_frame();
+            let now = local_now;
+            let max_columns = self.settings.max_columns.max(1);
+            let min_column_width = self.settings.min_column_width;
+            let options = RowOptions {
+                palette,
+                priority_display: self.settings.priority_display,
+                show_bar_number: self.settings.show_bar_number,
+                priority_side: self.settings.priority_side,
+                checkbox_side: self.settings.checkbox_side,
+                show_row_numbers: self.settings.show_row_numbers,
+                max_lines: self.settings.max_lines,
+                text_cleanup: self.settings.text_cleanup,
+                stale_after_days: self.settings.stale_after_days,
+                task_font: self.settings.task_font_id(),
+                known_contexts: &known_contexts,
+                now,
+                dragging: self.dragging_task.is_some(),
+            };
+            // Keep relative due times and overdue outlines current
+            if self.tasks.iter().any(|t| t.due.is_some() && !t.is_done()) {
+                ctx.request_repaint_after(Duration::from_secs(30));
+            }
 
-                    let len = self.tasks.len();
+            // Dragging a row reorders it, so the list only scrolls by wheel and scrollbar
+            let list = egui::ScrollArea::vertical()
+                .drag_to_scroll(false)
+                .show(ui, |ui| {
+                    // Wide windows fill columns top to bottom, left to right, keeping list order
+                    let columns =
+                        ((ui.available_width() / min_column_width) as usize).clamp(1, max_columns);
+                    let shown_count = self
+                        .tasks
+                        .iter()
+                        .filter(|t| self.filter.shows(t, now))
+                        .count();
+                    let done_count = self
+                        .tasks
+                        .iter()
+                        .filter(|t| t.is_done() && self.filter.shows(t, now))
+                        .count();
+                    // Sunk completed tasks get a divider, only when there is something on
+                    // both sides
+                    let divider = sink_completed && done_count > 0 && done_count < shown_count;
+                    let hide_done = divider && completed_collapsed;
+                    let shown_count = if hide_done {
+                        shown_count - done_count
+                    } else {
+                        shown_count
+                    };
+                    let per_column = shown_count.div_ceil(columns).max(1);
+                    // Where each shown row sits in the list, so the up/down buttons skip
+                    // hidden tasks
+                    let shown_rows: Vec<usize> = (0..self.tasks.len())
+                        .filter(|&i| {
+                            let task = &self.tasks[i];
+                            self.filter.shows(task, now) && !(hide_done && task.is_done())
+                        })
+                        .collect();
+                    let mut shown = 0;
+                    let mut divider_drawn = false;
+                    ui.columns(columns, |column_uis| {
+                        for (i, task) in self.tasks.iter_mut().enumerate() {
+                            if !self.filter.shows(task, now) {
+                                continue;
+                            }
+                            if divider && task.is_done() && !divider_drawn {
+                                divider_drawn = true;
+                                let ui = &mut column_uis[(shown / per_column).min(columns - 1)];
+                                let (chevron, hint) = if completed_collapsed {
+                                    ("⏷", "Show completed tasks")
+                                } else {
+                                    ("⏶", "Hide completed tasks")
+                                };
+                                ui.vertical_centered(|ui| {
+                                    let label =
+                                        format!("{} — Completed ({}) —", chevron, done_count);
+                                    if ui
+                                        .add(egui::Button::new(label).frame(false))
+                                        .on_hover_text(hint)
+                                        .clicked()
+                                    {
+                                        completed_collapsed = !completed_collapsed;
+                                    }
+                                });
+                                ui.add_space(4.0);
+                            }
+                            if hide_done && task.is_done() {
+                                continue;
+                            }
+                            let ui = &mut column_uis[shown / per_column];
+                            shown += 1;
+                            let above = shown.checked_sub(2).map(|row| shown_rows[row]);
+                            let below = shown_rows.get(shown).copied();
 
-                    let new_priority = if to == 0 {
-                        if len > 1 {
-                            self.tasks[1].priority.max(1).min(10)
-                        } else {
-                            self.tasks[to].priority
+                            if task
+                                .added_at
+                                .is_some_and(|added| added.elapsed() < ADDED_FLASH)
+                            {
+                                ctx.request_repaint_after(animation_frame);
+                            }
+                            let (row, actions) = show_task_row(
+                                ui,
+                                task,
+                                shown,
+                                (above, below),
+                                cursor == Some(task.id),
+                                &options,
+                            );
+                            if actions.clicked {
+                                self.cursor = Some(task.id);
+                            }
+                            if actions.drag_started {
+                                self.pressed_task = Some(task.id);
+                            }
+                            if actions.dragged_far
+                                && self.dragging_task.is_none()
+                                && self.pressed_task == Some(task.id)
+                            {
+                                self.dragging_task = Some(task.id);
+                            }
+                            if let Some(to) = actions.move_to {
+                                move_request = Some((i, to));
+                            }
+                            if actions.use_as_template {
+                                template_request = Some(i);
+                            }
+                            if actions.done_toggled {
+                                done_toggled = Some(i);
+                            }
+                            if actions.priority_edit_started {
+                                priority_edit_started = Some(i);
+                            }
+                            priority_changed |= actions.priority_changed;
+                            if let Some(scroll) = actions.priority_scroll {
+                                priority_scroll = Some((i, scroll));
+                            }
+                            // Thin bar along the bottom edge for tasks under way
+                            if task.progress > 0 && !task.is_done() {
+                                let rect = row.rect.shrink2(Vec2::new(8.0, 0.0));
+                                let width = rect.width() * task.progress.min(100) as f32 / 100.0;
+                                let bar = egui::Rect::from_min_size(
+                                    egui::pos2(rect.left(), rect.bottom() - 4.0),
+                                    Vec2::new(width, 3.0),
+                                );
+                                ui.painter().rect_filled(
+                                    bar,
+                                    egui::Rounding::same(1.5),
+                                    Color32::from_rgb(0, 150, 255),
+                                );
+                            }
+                            if let Some((_, align)) = scroll_to_task.filter(|&(t, _)| t == i) {
+                                row.scroll_to_me(align);
+                            }
+                            row_rects.push((i, row.rect));
+
+                            ui.add_space(4.0);
                         }
-                    } else if to == len - 1 {
-                        self.tasks[len - 2].priority.min(10).max(1)
+                    });
+                });
+            self.settings.completed_collapsed = completed_collapsed;
+            self.page_rows = row_rects
+                .iter()
+                .filter(|(_, rect)| list.inner_rect.contains_rect(*rect))
+                .count();
+
+            // Snapshot before the priority editor opens, so the edit can be undone
+            if let Some(i) = priority_edit_started {
+                self.priority_edit_snapshot = Some(self.tasks.clone());
+                self.tasks[i].editing_priority = true;
+            }
+
+            if priority_changed {
+                // Only keep the snapshot if a priority actually changed
+                if let Some(before) = self.priority_edit_snapshot.take() {
+                    let changed = before
+                        .iter()
+                        .zip(&self.tasks)
+                        .any(|(a, b)| a.priority != b.priority);
+                    if changed {
+                        self.undo_snapshot = Some(before);
+                        self.undo_taken_at = None;
+                    }
+                }
+                self.sort_tasks();
+            }
+
+            if let Some((from, to)) = move_request {
+                self.move_task(from, to);
+            }
+
+            if let Some(i) = done_toggled {
+                self.snapshot();
+                let task = &mut self.tasks[i];
+                task.status = if task.is_done() {
+                    Status::Active
+                } else {
+                    Status::Done
+                };
+                task.touch();
+                if task.is_done() {
+                    history::log_completion(task).ok();
+                }
+                if self.settings.sink_completed {
+                    self.sort_tasks();
+                }
+            }
+
+            if let Some((i, scroll)) = priority_scroll {
+                self.scroll_priority(i, scroll);
+            }
+
+            // Prefill the add row from a task so a variant can be tweaked and added
+            if let Some(i) = template_request {
+                let task = &self.tasks[i];
+                self.new_task_text = task.text.clone();
+                self.new_task_priority = task.shown_priority();
+                self.new_task_color = color32_from_array(task.color);
+                self.new_task_color_chosen = true;
+                self.focus_new_task = true;
+                ctx.request_repaint();
+            }
+
+            // While dragging, show where the task would land and drop it there on release
+            let dragged_row = self
+                .dragging_task
+                .and_then(|id| self.task_index(id))
+                .and_then(|from| row_rects.iter().copied().find(|&(i, _)| i == from));
+            if let Some((from, from_rect)) = dragged_row {
+                let pointer = ctx.input(|i| (i.pointer.interact_pos(), i.pointer.press_origin()));
+                if let (Some(pos), Some(origin)) = pointer {
+                    // Rows in the column under the pointer, or all of them between columns
+                    let in_column: Vec<(usize, egui::Rect)> = row_rects
+                        .iter()
+                        .copied()
+                        .filter(|(_, rect)| rect.x_range().contains(&pos.x))
+                        .collect();
+                    let candidates = if in_column.is_empty() {
+                        &row_rects
                     } else {
-                        let prev_p = self.tasks[to - 1].priority;
-                        let next_p = self.tasks[to + 1].priority;
-                        let low = prev_p.min(next_p);
-                        let high = prev_p.max(next_p);
-                        self.tasks[to].priority.clamp(low, high)
+                        &in_column
+                    };
+                    // The first row whose bottom is below the pointer, or the last row
+                    let (to, to_rect) = candidates
+                        .iter()
+                        .copied()
+                        .find(|(_, rect)| pos.y < rect.bottom())
+                        .unwrap_or(candidates[candidates.len() - 1]);
+
+                    // Insertion line on the side of the target the task will end up on
+                    let line_y = match to.cmp(&from) {
+                        std::cmp::Ordering::Less => Some(to_rect.top() - 2.0),
+                        std::cmp::Ordering::Greater => Some(to_rect.bottom() + 2.0),
+                        std::cmp::Ordering::Equal => None,
                     };
+                    if let Some(y) = line_y {
+                        ui.painter().hline(
+                            to_rect.x_range(),
+                            y,
+                            egui::Stroke::new(2.0, Color32::from_rgb(0, 150, 255)),
+                        );
+                    }
 
-                    self.tasks[to].priority = new_priority;
-                    //self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+                    // Semi-transparent ghost of the dragged row following the pointer
+                    let ghost_rect = from_rect.translate(pos - origin);
+                    let painter = ctx
+                        .layer_painter(egui::LayerId::new(
+                            egui::Order::Tooltip,
+                            egui::Id::new("drag_ghost"),
+                        ))
+                        .with_clip_rect(ghost_rect);
+                    let [r, g, b, _] = self.tasks[from].color;
+                    painter.rect_filled(
+                        ghost_rect,
+                        egui::Rounding::same(8.0),
+                        Color32::from_rgba_unmultiplied(r, g, b, 140),
+                    );
+                    painter.text(
+                        ghost_rect.left_center() + egui::vec2(12.0, 0.0),
+                        egui::Align2::LEFT_CENTER,
+                        &self.tasks[from].text,
+                        self.settings.task_font_id(),
+                        Color32::from_black_alpha(180),
+                    );
+                    ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+
+                    let over_trash = trash_rect.is_some_and(|rect| rect.contains(pos));
+                    if ctx.input(|i| i.pointer.any_released()) {
+                        if over_trash {
+                            self.delete_task(from);
+                        } else {
+                            self.drag_over_task = Some(to);
+                        }
+                    }
                 }
+            }
+
+            // After the loop, handle reordering and priority adjustment if drag completed
+            let dragged = self.dragging_task.and_then(|id| self.task_index(id));
+            if let (Some(from), Some(to)) = (dragged, self.drag_over_task) {
+                self.move_task(from, to);
 
                 self.dragging_task = None;
                 self.drag_over_task = None;
             }
 
-            ui.add_space(12.0);
+            // A release anywhere ends the press, even if it wasn't over a task
+            if ctx.input(|i| i.pointer.any_released()) {
+                self.pressed_task = None;
+                self.dragging_task = None;
+                self.drag_over_task = None;
+            }
+        });
 
-            // Trash button
-            let any_selected = self.tasks.iter().any(|t| t.selected);
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::BOTTOM), |ui| {
-                if ui
-                    .add_enabled(any_selected, egui::Button::new("🗑 Delete selected"))
-                    .clicked()
-                {
-                    self.last_deleted_tasks =
-                        self.tasks.iter().filter(|t| t.selected).cloned().collect();
+        self.expire_stale_undo();
+        self.update_streak(ctx);
+        self.write_recovery();
+    }
 
-                    self.tasks.retain(|t| !t.selected);
-                }
-            });
-        });
+    // Save before closing, and keep the window open if that fails
+    fn on_close_event(&mut self) -> bool {
+        if self.quit_without_saving {
+            return true;
+        }
+        self.take_inbox();
+        match self.persist_from_ui() {
+            Ok(()) => true,
+            Err(err) => {
+                self.close_save_error = Some(err.to_string());
+                false
+            }
+        }
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        self.persist_tasks();
+        self.take_inbox();
+        // Commit locally on the way out, leaving any push for the next run
+        let saved = self.persist_from_ui().is_ok();
+        inbox::unlock();
+        // A sync still running holds the repository's index lock, so let it finish
+        // first and skip the exit commit if it doesn't in time
+        let idle = self.git_sync_job.take().is_none_or(|job| {
+            !matches!(
+                job.recv_timeout(GIT_SYNC_EXIT_WAIT),
+                Err(mpsc::RecvTimeoutError::Timeout)
+            )
+        });
+        if saved && idle && self.settings.git_sync_enabled {
+            if let Ok(serialized) = serde_json::to_string_pretty(&self.tasks) {
+                let repo = Path::new(self.settings.git_repo_path.trim());
+                git_sync::sync(repo, &serialized, false).ok();
+            }
+        }
+        self.settings.persist();
     }
 }
 
-fn get_data_path() -> Option<PathBuf> {
+fn get_data_path(settings: &Settings) -> Option<PathBuf> {
+    let chosen = settings.data_path.trim();
+    if !chosen.is_empty() {
+        return Some(PathBuf::from(chosen));
+    }
     dirs::data_local_dir().map(|mut path| {
         path.push("rust_tasks.json");
         path
     })
 }
 
+fn get_quarantine_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks.quarantine.json");
+        path
+    })
+}
+
+fn get_recovery_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks.recovery.json");
+        path
+    })
+}
+
+// Add one task per non-empty line of stdin to the data file, without the GUI, or
+// hand them to the widget if it is open. A dry run lists what would be added and
+// leaves the file alone.
+fn import_stdin(dry_run: bool) -> std::io::Result<usize> {
+    let settings = Settings::load();
+    let mut app = MyApp {
+        tasks: MyApp::load_tasks(&settings),
+        new_task_priority: settings.default_priority,
+        settings,
+        ..Default::default()
+    };
+
+    let mut added = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        let before = app.tasks.len();
+        app.new_task_text = line.clone();
+        app.add_task();
+        if app.tasks.len() > before {
+            if dry_run {
+                println!("+ {}", app.settings.text_cleanup.apply(&line));
+            }
+            added.push(line);
+        }
+    }
+
+    if dry_run {
+        return Ok(added.len());
+    }
+    // An open widget would save its own copy over the file, so it gets the lines instead
+    if inbox::is_locked() {
+        inbox::append(&added)?;
+    } else {
+        app.persist_tasks()?;
+    }
+    Ok(added.len())
+}
+
 fn main() -> eframe::Result<()> {
-    let mut options = eframe::NativeOptions::default();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {}
+        ["import", "--stdin", rest @ ..] if rest.is_empty() || rest == ["--dry-run"] => {
+            let dry_run = !rest.is_empty();
+            match import_stdin(dry_run) {
+                Ok(added) if dry_run => println!("Would add {} tasks", added),
+                Ok(added) => println!("Added {} tasks", added),
+                Err(err) => {
+                    eprintln!("Import failed: {}", err);
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        ["capture"] => return capture::run(),
+        _ => {
+            eprintln!("Usage: task_widget [import --stdin [--dry-run] | capture]");
+            std::process::exit(2);
+        }
+    }
 
-    // Set initial window size here
-    options.initial_window_size = Some(Vec2::new(550.0, 450.0));
+    let options = eframe::NativeOptions {
+        // Set initial window size here
+        initial_window_size: Some(Vec2::new(550.0, 450.0)),
+        ..Default::default()
+    };
 
     eframe::run_native(
         "Nazario Lives",
         options,
-        Box::new(|_cc| {
+        Box::new(|cc| {
+            // Taken before loading, so other processes stop writing the file from here on
+            inbox::lock().ok();
+            let settings = Settings::load();
+            let font_error = fonts::apply(&cc.egui_ctx, &settings.font_path).err();
+            let (api, api_error) = if settings.api_enabled {
+                match api::ApiServer::start(settings.api_port, cc.egui_ctx.clone()) {
+                    Ok(server) => (Some(server), None),
+                    Err(err) => (None, Some(err)),
+                }
+            } else {
+                (None, None)
+            };
+            let (tasks, mut load_issues) = if settings.strict_load {
+                MyApp::load_tasks_strict(&settings)
+            } else {
+                (MyApp::load_tasks(&settings), Vec::new())
+            };
+            let repairs = validation::take_color_repairs();
+            if repairs > 0 {
+                load_issues.push(Diagnostic {
+                    entry: None,
+                    problem: format!("Repaired {} invalid task colors", repairs),
+                });
+            }
+            let last_recovery = serde_json::to_string(&tasks).unwrap_or_default();
+            // Only offer recovery if it actually differs from what was loaded
+            let recovered_tasks = MyApp::load_recovery(&settings).filter(|recovered| {
+                serde_json::to_string(recovered).unwrap_or_default() != last_recovery
+            });
+            // Selection is saved with the tasks; pick up navigation from it
+            let selected = tasks.iter().position(|t| t.selected);
+            let cursor = selected.map(|i| tasks[i].id);
             Box::new(MyApp {
-                tasks: MyApp::load_tasks(),
+                tasks,
+                new_task_priority: settings.default_priority,
+                settings,
+                font_error,
+                api,
+                api_error,
+                load_issues,
                 last_save: Instant::now(),
+                last_recovery,
+                recovered_tasks,
+                cursor,
+                scroll_to_task: selected.map(|i| (i, Some(egui::Align::Center))),
                 ..Default::default()
             })
         }),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app_with(tasks: &[(&str, u8)]) -> MyApp {
+        let mut app = MyApp::default();
+        for &(text, priority) in tasks {
+            app.insert_task(text.to_string(), priority, DEFAULT_TASK_COLOR);
+        }
+        app
+    }
+
+    fn texts(app: &MyApp) -> Vec<&str> {
+        app.tasks.iter().map(|t| t.text.as_str()).collect()
+    }
+
+    #[test]
+    fn undo_restores_order_and_priorities_after_a_drag() {
+        let mut app = app_with(&[("high", 9), ("mid", 5), ("low", 1)]);
+        let priorities: Vec<f32> = app.tasks.iter().map(|t| t.priority).collect();
+
+        app.move_task(2, 0);
+        assert_eq!(texts(&app), ["low", "high", "mid"]);
+        app.expire_stale_undo();
+        app.undo();
+
+        assert_eq!(texts(&app), ["high", "mid", "low"]);
+        let restored: Vec<f32> = app.tasks.iter().map(|t| t.priority).collect();
+        assert_eq!(restored, priorities);
+    }
+
+    #[test]
+    fn spreading_ties_at_the_bounds_keeps_them_distinct() {
+        let mut app = app_with(&[
+            ("a", 10),
+            ("b", 10),
+            ("c", 10),
+            ("d", 9),
+            ("e", 2),
+            ("f", 1),
+            ("g", 1),
+            ("h", 1),
+        ]);
+        spread_tied_priorities(&mut app.tasks);
+
+        let priorities: Vec<f32> = app.tasks.iter().map(|t| t.priority).collect();
+        assert!(
+            priorities.windows(2).all(|w| w[0] > w[1]),
+            "{:?}",
+            priorities
+        );
+        assert!(priorities.iter().all(|p| (1.0..=10.0).contains(p)));
+        let shown: Vec<u8> = app.tasks.iter().map(|t| t.shown_priority()).collect();
+        assert_eq!(shown, [10, 10, 10, 9, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn dropping_past_either_end_does_not_tie_with_the_neighbour() {
+        let mut app = app_with(&[
+            ("top", 10),
+            ("near top", 9),
+            ("near bottom", 2),
+            ("bottom", 1),
+        ]);
+        app.tasks[0].priority = 9.8;
+        app.tasks[3].priority = 1.2;
+
+        app.move_task(2, 0);
+        assert_eq!(app.tasks[0].text, "near bottom");
+        assert!(app.tasks[0].priority > app.tasks[1].priority);
+        assert!(app.tasks[0].priority <= 10.0);
+
+        app.move_task(1, 3);
+        assert_eq!(app.tasks[3].text, "top");
+        assert!(app.tasks[3].priority < app.tasks[2].priority);
+        assert!(app.tasks[3].priority >= 1.0);
+    }
+
+    #[test]
+    fn scrolling_less_than_a_step_leaves_the_priority_alone() {
+        let mut app = app_with(&[("a", 5)]);
+        app.tasks[0].priority = 5.3;
+
+        app.scroll_priority(0, PRIORITY_SCROLL_STEP / 2.0);
+        assert_eq!(app.tasks[0].priority, 5.3);
+        assert!(app.undo_snapshot.is_none());
+
+        app.scroll_priority(0, PRIORITY_SCROLL_STEP / 2.0);
+        assert_eq!(app.tasks[0].priority, 6.3);
+    }
+
+    #[test]
+    fn one_scroll_gesture_is_undone_in_one_step() {
+        let mut app = app_with(&[("a", 5)]);
+        for _ in 0..3 {
+            app.scroll_priority(0, PRIORITY_SCROLL_STEP);
+        }
+        assert_eq!(app.tasks[0].priority, 8.0);
+
+        app.expire_stale_undo();
+        app.undo();
+        assert_eq!(app.tasks[0].priority, 5.0);
+    }
+
+    #[test]
+    fn a_deleted_task_comes_back_below_its_old_neighbour() {
+        let mut app = app_with(&[("a", 9), ("b", 5), ("c", 1)]);
+        app.delete_task(1);
+        app.insert_task("new".to_string(), 10, DEFAULT_TASK_COLOR);
+
+        app.restore_deleted_for_edit();
+        assert_eq!(texts(&app), ["new", "a", "b", "c"]);
+        assert_eq!(app.cursor_index(), Some(2));
+    }
+
+    #[test]
+    fn the_cursor_follows_its_task_when_rows_shift() {
+        let mut app = app_with(&[("a", 9), ("b", 5)]);
+        app.cursor = Some(app.tasks[1].id);
+        app.insert_task("new".to_string(), 10, DEFAULT_TASK_COLOR);
+
+        assert_eq!(app.cursor_index(), Some(2));
+        assert_eq!(app.tasks[2].text, "b");
+    }
+
+    #[test]
+    fn sorting_by_priority_ignores_the_configured_keys() {
+        let mut app = app_with(&[("b", 5), ("a", 9)]);
+        app.settings.sort_keys = vec![sort::SortKey::Text];
+        app.sort_tasks();
+        assert_eq!(texts(&app), ["a", "b"]);
+        app.tasks[0].priority = 1.0;
+
+        app.sort_by_priority();
+        assert_eq!(texts(&app), ["b", "a"]);
+    }
+
+    #[test]
+    fn the_stale_filter_judges_against_the_time_passed_in() {
+        let mut app = app_with(&[("old", 5)]);
+        let now = Local::now();
+        app.tasks[0].created = Some(now - chrono::Duration::days(10));
+        let filter = Filter {
+            stale_only: Some(7),
+            ..Filter::default()
+        };
+
+        assert!(filter.shows(&app.tasks[0], now));
+        assert!(!filter.shows(&app.tasks[0], now - chrono::Duration::days(5)));
+    }
+
+    #[test]
+    fn moving_to_the_next_shown_row_skips_hidden_tasks() {
+        let mut app = app_with(&[("a", 9), ("hidden", 5), ("c", 1)]);
+        app.tasks[0].flagged = true;
+        app.tasks[2].flagged = true;
+        app.filter.flagged_only = true;
+
+        app.move_task(0, 2);
+        let shown: Vec<&str> = app
+            .tasks
+            .iter()
+            .filter(|t| app.filter.shows(t, Local::now()))
+            .map(|t| t.text.as_str())
+            .collect();
+        assert_eq!(shown, ["c", "a"]);
+    }
+
+    #[test]
+    fn undo_does_not_drop_changes_made_after_the_snapshot() {
+        let mut app = app_with(&[("A", 5)]);
+        app.delete_task(0);
+        app.expire_stale_undo();
+
+        app.new_task_text = "B".to_string();
+        app.add_task();
+        app.expire_stale_undo();
+        app.undo();
+
+        assert_eq!(texts(&app), ["B"]);
+    }
+
+    #[test]
+    fn undo_restores_each_color_after_a_recolor() {
+        let mut app = app_with(&[("a", 9), ("b", 5), ("c", 1)]);
+        let originals = [[200, 0, 0, 255], [0, 200, 0, 255], [0, 0, 200, 255]];
+        for (task, color) in app.tasks.iter_mut().zip(originals) {
+            task.color = color;
+        }
+        app.tasks[0].selected = true;
+        app.tasks[2].selected = true;
+
+        app.recolor_selected(Color32::YELLOW);
+        assert_eq!(app.tasks[0].color, array_from_color32(Color32::YELLOW));
+        assert_eq!(app.tasks[1].color, originals[1]);
+        app.expire_stale_undo();
+        app.undo();
+
+        let colors: Vec<[u8; 4]> = app.tasks.iter().map(|t| t.color).collect();
+        assert_eq!(colors, originals);
+    }
+
+    #[test]
+    fn tasks_entered_back_to_back_sort_once_adding_stops() {
+        let mut app = MyApp::default();
+        app.settings.defer_sort_while_adding = true;
+        app.settings.new_task_position = NewTaskPosition::Bottom;
+        for (text, priority) in [("low", 1), ("mid", 5), ("high", 9)] {
+            app.new_task_text = text.to_string();
+            app.new_task_priority = priority;
+            app.add_from_row(true);
+        }
+        assert_eq!(texts(&app), ["low", "mid", "high"]);
+        assert!(app.sort_pending);
+
+        app.end_bulk_add();
+        assert_eq!(texts(&app), ["high", "mid", "low"]);
+        assert!(!app.sort_pending);
+    }
+
+    #[test]
+    fn normalized_priorities_stay_distinct_past_ten_tasks() {
+        let names: Vec<String> = (0..25).map(|i| i.to_string()).collect();
+        let tasks: Vec<(&str, u8)> = names.iter().map(|n| (n.as_str(), 5)).collect();
+        let mut app = app_with(&tasks);
+
+        app.normalize_priorities();
+        let priorities: Vec<f32> = app.tasks.iter().map(|t| t.priority).collect();
+        assert_eq!(priorities.first(), Some(&10.0));
+        assert_eq!(priorities.last(), Some(&1.0));
+        assert!(priorities.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn waiting_tasks_are_never_overdue() {
+        let mut app = app_with(&[("report", 5)]);
+        let task = &mut app.tasks[0];
+        task.due = NaiveDate::from_ymd_opt(2020, 1, 1);
+        assert!(task.is_overdue(Local::now()));
+        task.status = Status::Waiting;
+        assert!(!task.is_overdue(Local::now()));
+    }
+
+    #[test]
+    fn clearing_the_text_while_editing_keeps_the_old_text() {
+        let mut app = app_with(&[("Buy milk", 5)]);
+        let task = &mut app.tasks[0];
+        task.start_editing();
+        task.text.clear();
+        task.finish_editing(TextCleanup::default());
+
+        assert_eq!(task.text, "Buy milk");
+        assert!(!task.editing);
+    }
+
+    #[test]
+    fn whitespace_only_text_also_keeps_the_old_text() {
+        let mut app = app_with(&[("Buy milk", 5)]);
+        let task = &mut app.tasks[0];
+        task.start_editing();
+        task.text = "   ".to_string();
+        task.finish_editing(TextCleanup::default());
+
+        assert_eq!(task.text, "Buy milk");
+    }
+
+    #[test]
+    fn api_tasks_are_cleaned_up_like_typed_ones() {
+        let mut app = app_with(&[]);
+        app.settings.text_cleanup.collapse_whitespace = true;
+        app.settings.text_cleanup.capitalize_first = true;
+
+        let reply = app.answer_api_call(api::ApiCall::Add {
+            text: "   ".to_string(),
+            priority: None,
+        });
+        assert_eq!(reply.status, 400);
+        assert!(app.tasks.is_empty());
+
+        let path = std::env::temp_dir().join(format!("task_widget_api_{}.json", Uuid::new_v4()));
+        app.settings.data_path = path.to_string_lossy().into_owned();
+        app.recovered_tasks = Some(Vec::new());
+        app.answer_api_call(api::ApiCall::Add {
+            text: "  water   plants ".to_string(),
+            priority: None,
+        });
+        fs::remove_file(path).ok();
+        assert_eq!(texts(&app), ["Water plants"]);
+    }
+
+    #[test]
+    fn api_tasks_without_a_priority_use_the_default_from_settings() {
+        let mut app = app_with(&[]);
+        let path = std::env::temp_dir().join(format!("task_widget_api_{}.json", Uuid::new_v4()));
+        app.settings.data_path = path.to_string_lossy().into_owned();
+        app.settings.default_priority = 6;
+        // Keeps the save away from the real recovery file
+        app.recovered_tasks = Some(Vec::new());
+
+        app.answer_api_call(api::ApiCall::Add {
+            text: "Water plants".to_string(),
+            priority: None,
+        });
+        app.answer_api_call(api::ApiCall::Add {
+            text: "Call mum".to_string(),
+            priority: Some(2),
+        });
+        fs::remove_file(path).ok();
+
+        let plants = app.tasks.iter().find(|t| t.text == "Water plants").unwrap();
+        assert_eq!(plants.priority, 6.0);
+        let mum = app.tasks.iter().find(|t| t.text == "Call mum").unwrap();
+        assert_eq!(mum.priority, 2.0);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::Task;
+
+// What a merge did, for reporting back to the user
+#[derive(Default)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+// Merge `incoming` into `tasks`, matching tasks by id, or by text for copies that
+// got their ids separately. Where both sides have a task, the more recently
+// modified version wins; ties keep the current one.
+pub fn merge(tasks: &mut Vec<Task>, incoming: Vec<Task>) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+    for mut theirs in incoming {
+        theirs.selected = false;
+        let same = tasks
+            .iter()
+            .position(|ours| ours.id == theirs.id)
+            .or_else(|| {
+                tasks
+                    .iter()
+                    .position(|ours| ours.text.trim() == theirs.text.trim())
+            });
+        match same.map(|i| &mut tasks[i]) {
+            None => {
+                tasks.push(theirs);
+                summary.added += 1;
+            }
+            Some(ours) if theirs.modified > ours.modified => {
+                theirs.selected = ours.selected;
+                *ours = theirs;
+                summary.updated += 1;
+            }
+            Some(_) => summary.unchanged += 1,
+        }
+    }
+    summary
+}
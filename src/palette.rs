@@ -0,0 +1,87 @@
+use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
+
+// Which built-in palette the presets and priority gradient come from
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaletteKind {
+    #[default]
+    Standard,
+    ColorblindSafe,
+}
+
+impl PaletteKind {
+    pub const ALL: [PaletteKind; 2] = [PaletteKind::Standard, PaletteKind::ColorblindSafe];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteKind::Standard => "Standard",
+            PaletteKind::ColorblindSafe => "Colorblind safe",
+        }
+    }
+
+    pub fn palette(self) -> &'static Palette {
+        match self {
+            PaletteKind::Standard => &STANDARD,
+            PaletteKind::ColorblindSafe => &COLORBLIND_SAFE,
+        }
+    }
+}
+
+pub struct Palette {
+    // Swatches shown in the preset row
+    pub presets: &'static [(Color32, &'static str)],
+    // Color stops for priority 1 through 10, lowest first
+    pub gradient: &'static [Color32],
+}
+
+pub const STANDARD: Palette = Palette {
+    presets: &[
+        (Color32::LIGHT_GREEN, "Light green"),
+        (Color32::LIGHT_YELLOW, "Light yellow"),
+        (Color32::LIGHT_RED, "Light red"),
+        (Color32::LIGHT_BLUE, "Light blue"),
+        (Color32::WHITE, "White"),
+        (Color32::DARK_RED, "Dark red"),
+        (Color32::DARK_GREEN, "Dark green"),
+    ],
+    gradient: &[Color32::from_rgb(255, 165, 0)],
+};
+
+// Okabe-Ito swatches and a viridis gradient, both distinguishable without red/green
+pub const COLORBLIND_SAFE: Palette = Palette {
+    presets: &[
+        (Color32::from_rgb(230, 159, 0), "Orange"),
+        (Color32::from_rgb(86, 180, 233), "Sky blue"),
+        (Color32::from_rgb(0, 158, 115), "Bluish green"),
+        (Color32::from_rgb(240, 228, 66), "Yellow"),
+        (Color32::WHITE, "White"),
+        (Color32::from_rgb(0, 114, 178), "Blue"),
+        (Color32::from_rgb(213, 94, 0), "Vermillion"),
+        (Color32::from_rgb(204, 121, 167), "Reddish purple"),
+    ],
+    gradient: &[
+        Color32::from_rgb(68, 1, 84),
+        Color32::from_rgb(59, 82, 139),
+        Color32::from_rgb(33, 145, 140),
+        Color32::from_rgb(94, 201, 98),
+        Color32::from_rgb(253, 231, 37),
+    ],
+};
+
+impl Palette {
+    // Interpolate along the gradient for a priority in 1..=10
+    pub fn priority_color(&self, priority: f32) -> Color32 {
+        let stops = self.gradient;
+        if stops.len() == 1 {
+            return stops[0];
+        }
+
+        let t = (priority.clamp(1.0, 10.0) - 1.0) / 9.0 * (stops.len() - 1) as f32;
+        let i = (t.floor() as usize).min(stops.len() - 2);
+        let f = t - i as f32;
+        let (a, b) = (stops[i], stops[i + 1]);
+        let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * f).round() as u8;
+
+        Color32::from_rgb(mix(a.r(), b.r()), mix(a.g(), b.g()), mix(a.b(), b.b()))
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+
+// How often the review reminder comes round
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReviewFrequency {
+    #[default]
+    Daily,
+    Weekly(Weekday),
+}
+
+impl ReviewFrequency {
+    pub const ALL: [ReviewFrequency; 8] = [
+        ReviewFrequency::Daily,
+        ReviewFrequency::Weekly(Weekday::Mon),
+        ReviewFrequency::Weekly(Weekday::Tue),
+        ReviewFrequency::Weekly(Weekday::Wed),
+        ReviewFrequency::Weekly(Weekday::Thu),
+        ReviewFrequency::Weekly(Weekday::Fri),
+        ReviewFrequency::Weekly(Weekday::Sat),
+        ReviewFrequency::Weekly(Weekday::Sun),
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            ReviewFrequency::Daily => "Every day".to_string(),
+            ReviewFrequency::Weekly(day) => format!("Every {}", day),
+        }
+    }
+}
+
+// The most recent scheduled reminder at or before `now`
+pub fn latest_occurrence(
+    frequency: ReviewFrequency,
+    hour: u32,
+    minute: u32,
+    now: DateTime<Local>,
+) -> Option<DateTime<Local>> {
+    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+    let (days_back, period) = match frequency {
+        ReviewFrequency::Daily => (0, 1),
+        ReviewFrequency::Weekly(day) => {
+            let back = (now.weekday().num_days_from_monday() + 7 - day.num_days_from_monday()) % 7;
+            (back as i64, 7)
+        }
+    };
+
+    let date = now.date_naive() - Duration::days(days_back);
+    // A time skipped by a DST change fires at the next hour instead
+    let at = |date: chrono::NaiveDate| {
+        let naive = date.and_time(time);
+        Local.from_local_datetime(&naive).earliest().or_else(|| {
+            Local
+                .from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+    };
+    let occurrence = at(date)?;
+    if occurrence <= now {
+        Some(occurrence)
+    } else {
+        at(date - Duration::days(period))
+    }
+}
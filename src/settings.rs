@@ -0,0 +1,342 @@
+use crate::palette::PaletteKind;
+use crate::review::ReviewFrequency;
+use crate::sort::SortKey;
+use crate::Status;
+use chrono::{DateTime, Local, Timelike};
+use eframe::egui::{FontFamily, FontId};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// Where add_task puts a new task among tasks of equal priority
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NewTaskPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+// How a task's priority is drawn on its row
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PriorityDisplay {
+    #[default]
+    Number,
+    Bar,
+    // Order only changes by dragging; priorities are still kept for sorting
+    Hidden,
+}
+
+// Which side of the task text a row control sits on
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Side {
+    #[default]
+    Left,
+    Right,
+}
+
+// Screen edge the window is pinned to as a full-height sidebar, if any
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dock {
+    #[default]
+    Floating,
+    Left,
+    Right,
+}
+
+// Font family used for task text
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskFont {
+    #[default]
+    Proportional,
+    Monospace,
+}
+
+// Tidying applied to task text when a task is added or an edit is committed;
+// surrounding whitespace is always trimmed
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct TextCleanup {
+    pub collapse_whitespace: bool,
+    pub capitalize_first: bool,
+}
+
+impl TextCleanup {
+    pub fn apply(self, text: &str) -> String {
+        let mut text = if self.collapse_whitespace {
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        } else {
+            text.trim().to_string()
+        };
+        if self.capitalize_first {
+            if let Some(first) = text.chars().next() {
+                text = first.to_uppercase().chain(text.chars().skip(1)).collect();
+            }
+        }
+        text
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub palette: PaletteKind,
+    pub new_task_position: NewTaskPosition,
+    // Priority the add row starts at, and whether it keeps the last one used instead
+    pub default_priority: u8,
+    pub remember_last_priority: bool,
+    // Default color of a new task for each priority, lowest first
+    pub priority_colors_enabled: bool,
+    pub priority_colors: Vec<[u8; 4]>,
+    // Validate each task on load and quarantine bad ones instead of failing the whole file
+    pub strict_load: bool,
+    // Lines of task text shown before clipping with an ellipsis, 0 for no limit
+    pub max_lines: usize,
+    pub text_cleanup: TextCleanup,
+    // Position of each shown row before it, counting from 1
+    pub show_row_numbers: bool,
+    // Unfinished tasks created this many days ago get an aged look, 0 for never
+    pub stale_after_days: u32,
+    // Up to this many columns side by side once the window is wide enough
+    pub max_columns: usize,
+    pub min_column_width: f32,
+    pub dock: Dock,
+    pub dock_width: f32,
+    // Show the status board instead of the list, with these columns in order
+    pub board_view: bool,
+    pub board_columns: Vec<Status>,
+    // Sort done tasks below incomplete ones regardless of priority
+    pub sink_completed: bool,
+    // Hold the sort while tasks are entered back to back in the add row
+    pub defer_sort_while_adding: bool,
+    // Keys the list is sorted by, most significant first
+    pub sort_keys: Vec<SortKey>,
+    // Wait this long after the last scroll over a priority before moving the row
+    pub resort_delay_ms: u64,
+    // Cap for redraws while something animates; otherwise the window only
+    // redraws on input or when a timer is due
+    pub max_fps: u32,
+    pub priority_display: PriorityDisplay,
+    // Overlay the number on the priority bar
+    pub show_bar_number: bool,
+    pub priority_side: Side,
+    pub checkbox_side: Side,
+    // Hide the header, add row and presets
+    pub controls_collapsed: bool,
+    // Count consecutive days with a completion in the menu bar, cheering at milestones
+    pub streak_enabled: bool,
+    // Fold away the completed tasks below the divider when they sink to the bottom
+    pub completed_collapsed: bool,
+    // Big title above the add row
+    pub header_shown: bool,
+    pub header_icon: String,
+    pub header_text: String,
+    pub header_color: [u8; 4],
+    // TTF/OTF file used in place of the default proportional font, empty for none
+    pub font_path: String,
+    pub task_font: TaskFont,
+    pub task_font_size: f32,
+    // Bring the window forward with a prompt at a scheduled time
+    pub review_enabled: bool,
+    pub review_frequency: ReviewFrequency,
+    pub review_hour: u32,
+    pub review_minute: u32,
+    pub review_message: String,
+    // When the reminder last fired, so each occurrence only fires once
+    pub review_last_fired: Option<DateTime<Local>>,
+    // Hold back reminders, either until switched off or during the daily quiet hours
+    pub do_not_disturb: bool,
+    pub quiet_hours_enabled: bool,
+    pub quiet_start_hour: u32,
+    pub quiet_end_hour: u32,
+    // Show held back reminders once quiet ends instead of dropping them
+    pub queue_while_quiet: bool,
+    // Deleting at least this many tasks at once asks first, 0 never asks
+    pub confirm_delete_at: usize,
+    // Data file to use instead of the one in the data folder; empty for the default
+    pub data_path: String,
+    // Commit rust_tasks.json into this repository on each save
+    pub git_sync_enabled: bool,
+    pub git_repo_path: String,
+    pub git_push: bool,
+    // Serve the tasks over HTTP on localhost
+    pub api_enabled: bool,
+    pub api_port: u16,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            palette: PaletteKind::default(),
+            new_task_position: NewTaskPosition::default(),
+            default_priority: 5,
+            remember_last_priority: false,
+            priority_colors_enabled: false,
+            priority_colors: default_priority_colors(),
+            strict_load: false,
+            max_lines: 0,
+            text_cleanup: TextCleanup::default(),
+            show_row_numbers: false,
+            stale_after_days: 0,
+            max_columns: 1,
+            min_column_width: 320.0,
+            dock: Dock::default(),
+            dock_width: 360.0,
+            board_view: false,
+            board_columns: Status::ALL.to_vec(),
+            sink_completed: false,
+            defer_sort_while_adding: false,
+            sort_keys: vec![SortKey::Priority],
+            resort_delay_ms: 800,
+            max_fps: 30,
+            priority_display: PriorityDisplay::default(),
+            show_bar_number: false,
+            priority_side: Side::default(),
+            checkbox_side: Side::default(),
+            controls_collapsed: false,
+            streak_enabled: false,
+            completed_collapsed: false,
+            header_shown: true,
+            header_icon: "📋".to_string(),
+            header_text: "Tasks".to_string(),
+            header_color: [0, 150, 255, 255],
+            font_path: String::new(),
+            task_font: TaskFont::default(),
+            task_font_size: 16.0,
+            review_enabled: false,
+            review_frequency: ReviewFrequency::default(),
+            review_hour: 9,
+            review_minute: 0,
+            review_message: "Review your tasks".to_string(),
+            review_last_fired: None,
+            do_not_disturb: false,
+            quiet_hours_enabled: false,
+            quiet_start_hour: 22,
+            quiet_end_hour: 7,
+            queue_while_quiet: false,
+            confirm_delete_at: 0,
+            data_path: String::new(),
+            git_sync_enabled: false,
+            git_repo_path: String::new(),
+            git_push: false,
+            api_enabled: false,
+            api_port: 7878,
+        }
+    }
+}
+
+impl Settings {
+    pub fn task_font_id(&self) -> FontId {
+        let family = match self.task_font {
+            TaskFont::Proportional => FontFamily::Proportional,
+            TaskFont::Monospace => FontFamily::Monospace,
+        };
+        FontId::new(self.task_font_size, family)
+    }
+
+    // The mapped color for a priority, or None when the mapping is switched off
+    pub fn priority_color(&self, priority: u8) -> Option<[u8; 4]> {
+        if !self.priority_colors_enabled {
+            return None;
+        }
+        self.priority_colors
+            .get((priority as usize).checked_sub(1)?)
+            .copied()
+    }
+
+    pub fn animation_frame(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.max_fps.max(1) as f32)
+    }
+
+    // Quiet hours can run past midnight, e.g. 22 to 7
+    pub fn is_quiet(&self, now: DateTime<Local>) -> bool {
+        if self.do_not_disturb {
+            return true;
+        }
+        if !self.quiet_hours_enabled {
+            return false;
+        }
+        let (start, end, hour) = (self.quiet_start_hour, self.quiet_end_hour, now.hour());
+        if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        }
+    }
+
+    pub fn load() -> Self {
+        if let Some(path) = get_settings_path() {
+            if let Ok(data) = fs::read_to_string(path) {
+                if let Ok(settings) = serde_json::from_str(&data) {
+                    return settings;
+                }
+            }
+        }
+        Self::default()
+    }
+
+    pub fn persist(&self) {
+        if let Some(path) = get_settings_path() {
+            if let Ok(serialized) = serde_json::to_string_pretty(self) {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir).ok();
+                }
+                fs::write(path, serialized).ok();
+            }
+        }
+    }
+}
+
+fn get_settings_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|mut path| {
+        path.push("rust_tasks_settings.json");
+        path
+    })
+}
+
+// Green for the lowest priority through to red for the highest
+fn default_priority_colors() -> Vec<[u8; 4]> {
+    (0..10)
+        .map(|step| {
+            let t = step as f32 / 9.0;
+            let mix = |low: f32, high: f32| (low + (high - low) * t).round() as u8;
+            [mix(150.0, 235.0), mix(215.0, 120.0), mix(150.0, 110.0), 255]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cleanup(collapse_whitespace: bool, capitalize_first: bool) -> TextCleanup {
+        TextCleanup {
+            collapse_whitespace,
+            capitalize_first,
+        }
+    }
+
+    #[test]
+    fn text_cleanup_cases() {
+        let cases = [
+            (
+                cleanup(false, false),
+                "  call  the   bank \n",
+                "call  the   bank",
+            ),
+            (
+                cleanup(true, false),
+                "  call  the \t bank \n",
+                "call the bank",
+            ),
+            (cleanup(false, true), " call the bank", "Call the bank"),
+            (cleanup(false, true), "écrire à Anne", "Écrire à Anne"),
+            (cleanup(false, true), "ßtraße", "SStraße"),
+            (cleanup(true, true), "   ", ""),
+            (cleanup(false, false), "", ""),
+        ];
+        for (cleanup, input, expected) in cases {
+            assert_eq!(cleanup.apply(input), expected, "{:?}", input);
+        }
+    }
+}
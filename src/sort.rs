@@ -0,0 +1,104 @@
+use crate::Task;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+// One key of the task order; later keys only break ties left by earlier ones
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Priority,
+    Flagged,
+    Due,
+    Created,
+    Modified,
+    Text,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 6] = [
+        SortKey::Priority,
+        SortKey::Flagged,
+        SortKey::Due,
+        SortKey::Created,
+        SortKey::Modified,
+        SortKey::Text,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Priority => "Priority, highest first",
+            SortKey::Flagged => "Flagged first",
+            SortKey::Due => "Due soonest first",
+            SortKey::Created => "Oldest first",
+            SortKey::Modified => "Recently changed first",
+            SortKey::Text => "Text, A to Z",
+        }
+    }
+
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        match self {
+            SortKey::Priority => b.priority.total_cmp(&a.priority),
+            SortKey::Flagged => b.flagged.cmp(&a.flagged),
+            // Tasks without a due date go after every dated one
+            SortKey::Due => {
+                let (a, b) = (a.due_at(), b.due_at());
+                (a.is_none(), a).cmp(&(b.is_none(), b))
+            }
+            // Tasks from before these were recorded count as oldest
+            SortKey::Created => a.created.cmp(&b.created),
+            SortKey::Modified => b.modified.cmp(&a.modified),
+            SortKey::Text => a.text.to_lowercase().cmp(&b.text.to_lowercase()),
+        }
+    }
+}
+
+pub const PRESETS: [(&str, &[SortKey]); 5] = [
+    ("Priority", &[SortKey::Priority]),
+    ("Priority, then due", &[SortKey::Priority, SortKey::Due]),
+    (
+        "Priority, then oldest",
+        &[SortKey::Priority, SortKey::Created],
+    ),
+    (
+        "Priority, then flagged",
+        &[SortKey::Priority, SortKey::Flagged],
+    ),
+    (
+        "Flagged, then priority",
+        &[SortKey::Flagged, SortKey::Priority],
+    ),
+];
+
+pub fn compare(keys: &[SortKey], a: &Task, b: &Task) -> Ordering {
+    keys.iter().fold(Ordering::Equal, |order, key| {
+        order.then_with(|| key.compare(a, b))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(text: &str, priority: u8, due: Option<&str>) -> Task {
+        serde_json::from_value(json!({
+            "text": text,
+            "priority": priority,
+            "selected": false,
+            "due": due,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn priority_then_due_puts_undated_tasks_last() {
+        let mut tasks = [
+            task("undated", 5, None),
+            task("later", 5, Some("2024-06-01")),
+            task("low", 1, Some("2024-01-01")),
+            task("sooner", 5, Some("2024-05-01")),
+        ];
+        tasks.sort_by(|a, b| compare(&[SortKey::Priority, SortKey::Due], a, b));
+        let order: Vec<&str> = tasks.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(order, ["sooner", "later", "undated", "low"]);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{array_from_color32, Task, DEFAULT_TASK_COLOR};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::cell::Cell;
+use std::fmt;
+
+thread_local! {
+    // Colors fixed up by deserialize_color since the last take_color_repairs
+    static COLOR_REPAIRS: Cell<usize> = const { Cell::new(0) };
+}
+
+// A problem found while strictly loading the task file
+pub struct Diagnostic {
+    // 1-based position in the file, or None for problems with the file as a whole
+    pub entry: Option<usize>,
+    pub problem: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entry {
+            Some(entry) => write!(f, "Task {}: {}", entry, self.problem),
+            None => write!(f, "{}", self.problem),
+        }
+    }
+}
+
+pub struct StrictLoad {
+    pub tasks: Vec<Task>,
+    // Raw entries that failed to parse or validate, kept so they aren't lost
+    pub quarantined: Vec<Value>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// Problems with a task that parsed but breaks the app's expectations
+pub fn validate_task(task: &Task) -> Vec<String> {
+    let mut problems = Vec::new();
+    if task.text.trim().is_empty() {
+        problems.push("text is empty".to_string());
+    }
+    if !(1.0..=10.0).contains(&task.priority) {
+        problems.push(format!("priority {} is outside 1-10", task.priority));
+    }
+    problems
+}
+
+// Parse each entry on its own so one bad task doesn't take the rest with it
+pub fn load_strict(data: &str) -> StrictLoad {
+    let mut result = StrictLoad {
+        tasks: Vec::new(),
+        quarantined: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    let entries = match serde_json::from_str(data) {
+        Ok(Value::Array(entries)) => Ok(entries),
+        Ok(_) => Err("file is not a list of tasks".to_string()),
+        Err(err) => Err(format!("file is not valid JSON: {}", err)),
+    };
+
+    // Keep the whole file's text if it can't be read as a list at all
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(problem) => {
+            result.diagnostics.push(Diagnostic {
+                entry: None,
+                problem,
+            });
+            result.quarantined.push(Value::String(data.to_string()));
+            return result;
+        }
+    };
+
+    for (i, entry) in entries.into_iter().enumerate() {
+        // Repaired colors still load, but say which entry had one
+        if let Some(value) = entry.get("color") {
+            let (color, repaired) = repair_color(value);
+            if repaired {
+                result.diagnostics.push(Diagnostic {
+                    entry: Some(i + 1),
+                    problem: format!("color {} was repaired to {:?}", value, color),
+                });
+            }
+        }
+        let problems = match serde_json::from_value::<Task>(entry.clone()) {
+            Ok(task) => {
+                let problems = validate_task(&task);
+                if problems.is_empty() {
+                    result.tasks.push(task);
+                    continue;
+                }
+                problems
+            }
+            Err(err) => vec![err.to_string()],
+        };
+
+        result
+            .diagnostics
+            .extend(problems.into_iter().map(|problem| Diagnostic {
+                entry: Some(i + 1),
+                problem,
+            }));
+        result.quarantined.push(entry);
+    }
+
+    // Already reported per entry, so the file-wide count starts over
+    take_color_repairs();
+    result
+}
+
+// Read a task color without failing the whole file: out-of-range channels are
+// clamped, a missing alpha becomes opaque, extra channels are dropped, and
+// anything else falls back to the default color
+pub fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 4], D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    let (color, repaired) = repair_color(&value);
+    if repaired {
+        COLOR_REPAIRS.with(|count| count.set(count.get() + 1));
+    }
+    Ok(color)
+}
+
+fn repair_color(value: &Value) -> ([u8; 4], bool) {
+    let default = (array_from_color32(DEFAULT_TASK_COLOR), true);
+    let Some(items) = value.as_array() else {
+        return default;
+    };
+    if items.len() < 3 {
+        return default;
+    }
+
+    let mut color = [0, 0, 0, 255];
+    let mut repaired = items.len() != 4;
+    for (channel, item) in color.iter_mut().zip(items) {
+        let Some(n) = item.as_f64() else {
+            return default;
+        };
+        let clamped = n.round().clamp(0.0, 255.0);
+        repaired |= clamped != n;
+        *channel = clamped as u8;
+    }
+    (color, repaired)
+}
+
+// How many colors were repaired since the last call
+pub fn take_color_repairs() -> usize {
+    COLOR_REPAIRS.with(|count| count.replace(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    #[test]
+    fn repair_color_cases() {
+        let cases = [
+            (json!([10, 20, 30, 40]), [10, 20, 30, 40], false),
+            (json!([10, 20]), WHITE, true),
+            (json!([10, 20, 30]), [10, 20, 30, 255], true),
+            (json!([1, 2, 3, 4, 5]), [1, 2, 3, 4], true),
+            (json!([300, -5, 0, 255]), [255, 0, 0, 255], true),
+            (json!([1.6, 2, 3, 4]), [2, 2, 3, 4], true),
+            (json!(["red", 0, 0, 255]), WHITE, true),
+            (json!(null), WHITE, true),
+        ];
+        for (value, color, repaired) in cases {
+            assert_eq!(repair_color(&value), (color, repaired), "{}", value);
+        }
+    }
+
+    #[test]
+    fn strict_load_names_the_entry_with_a_bad_color() {
+        let data = json!([
+            {"text": "fine", "priority": 5, "selected": false, "color": [1, 2, 3, 4]},
+            {"text": "short", "priority": 5, "selected": false, "color": [1, 2]},
+        ])
+        .to_string();
+        let result = load_strict(&data);
+
+        assert_eq!(result.tasks.len(), 2);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].entry, Some(2));
+        assert_eq!(take_color_repairs(), 0);
+    }
+}